@@ -0,0 +1,247 @@
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{pretty_number, Effect, EffectWithSource, GameState, Item, UiRect, WoodItem, WoodWorkingItem};
+use crate::job::{JobArchetype, JobModifiers};
+use crate::job_slot::JobSlotState;
+use crate::palette;
+use crate::skill::SkillArchetype;
+use crate::toasts::{Toast, TOAST_SECONDS};
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Achievement {
+    Lumberjack,
+    Carpenter,
+    Ironmonger,
+    MasterChef,
+    Hyperactive,
+    FullHouse,
+}
+
+/// What has to happen for an achievement to unlock.
+pub enum AchievementGoal {
+    ProduceItem(Item, i64),
+    SkillLevel(SkillArchetype, i64),
+    HyperActivations(i64),
+    AllSlotsRunning,
+}
+
+impl Achievement {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Achievement::Lumberjack => "Lumberjack",
+            Achievement::Carpenter => "Carpenter",
+            Achievement::Ironmonger => "Ironmonger",
+            Achievement::MasterChef => "Master Chef",
+            Achievement::Hyperactive => "Hyperactive",
+            Achievement::FullHouse => "Full House",
+        }
+    }
+
+    pub fn get_goal(&self) -> AchievementGoal {
+        match self {
+            Achievement::Lumberjack => AchievementGoal::ProduceItem(Item::Wood(WoodItem::Craftwood), 1_000),
+            Achievement::Carpenter => AchievementGoal::ProduceItem(Item::Woodworking(WoodWorkingItem::Plank), 250),
+            Achievement::Ironmonger => AchievementGoal::ProduceItem(Item::IronBar, 500),
+            Achievement::MasterChef => AchievementGoal::SkillLevel(SkillArchetype::Cooking, 20),
+            Achievement::Hyperactive => AchievementGoal::HyperActivations(100),
+            Achievement::FullHouse => AchievementGoal::AllSlotsRunning,
+        }
+    }
+
+    pub fn get_description(&self) -> String {
+        match self.get_goal() {
+            AchievementGoal::ProduceItem(item, amount) => format!("Make {} {}", pretty_number(amount), item.get_name()),
+            AchievementGoal::SkillLevel(skill, level) => format!("Reach {} {}", skill.get_name(), level),
+            AchievementGoal::HyperActivations(times) => format!("Activate hyper {} times", times),
+            AchievementGoal::AllSlotsRunning => "Run a job in every slot".to_string(),
+        }
+    }
+
+    pub fn get_reward_description(&self) -> Option<&str> {
+        match self {
+            Achievement::Lumberjack => Some("+5% Lumbering speed"),
+            Achievement::MasterChef => Some("+5% Cooking yield"),
+            Achievement::Hyperactive => Some("Hyper mode 10% stronger"),
+            Achievement::FullHouse => Some("+2% speed for all jobs"),
+            Achievement::Carpenter | Achievement::Ironmonger => None,
+        }
+    }
+
+    /// The small permanent bonus of an unlocked achievement.
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        let skill_type = job_archetype.get_skill_type();
+
+        match self {
+            Achievement::Lumberjack if skill_type == SkillArchetype::Lumbering => JobModifiers { speed_multiplier: 1.05, ..JobModifiers::none() },
+            Achievement::MasterChef if skill_type == SkillArchetype::Cooking => JobModifiers { yield_multiplier: 1.05, ..JobModifiers::none() },
+            Achievement::Hyperactive => JobModifiers { hyper_multiplier: 1.1, ..JobModifiers::none() },
+            Achievement::FullHouse => JobModifiers { speed_multiplier: 1.02, ..JobModifiers::none() },
+            _ => JobModifiers::none(),
+        }
+    }
+
+    /// Current and required progress towards the goal.
+    pub fn get_progress(&self, state: &GameState) -> (i64, i64) {
+        match self.get_goal() {
+            AchievementGoal::ProduceItem(item, amount) => (state.achievements.get_produced(&item), amount),
+            AchievementGoal::SkillLevel(skill, level) => {
+                (state.skill_archetype_instances.get_skill_by_type(&skill).actions_counter.level, level)
+            }
+            AchievementGoal::HyperActivations(times) => (state.achievements.hyper_activations, times),
+            AchievementGoal::AllSlotsRunning => {
+                let running = state.job_slots.iter()
+                    .filter(|slot| matches!(slot.state, JobSlotState::RunningJob(_)))
+                    .count();
+                (running as i64, state.job_slots.len() as i64)
+            }
+        }
+    }
+}
+
+/// Unlocked achievements and the statistics they are tracked with.
+pub struct Achievements {
+    pub unlocked: Vec<Achievement>,
+    pub produced: HashMap<Item, i64>,
+    pub hyper_activations: i64,
+}
+
+impl Achievements {
+    pub fn new() -> Self {
+        Self {
+            unlocked: vec![],
+            produced: HashMap::new(),
+            hyper_activations: 0,
+        }
+    }
+
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.unlocked.contains(achievement)
+    }
+
+    pub fn get_produced(&self, item: &Item) -> i64 {
+        *self.produced.get(item).unwrap_or(&0)
+    }
+
+    pub fn record_effects(&mut self, effects: &[EffectWithSource]) {
+        for EffectWithSource::JobSource { effect, .. } in effects {
+            if let Effect::AddItem { item, amount } = effect {
+                *self.produced.entry(*item).or_default() += amount;
+            }
+        }
+    }
+
+    /// Returns false if the achievement was already unlocked.
+    pub fn unlock(&mut self, achievement: Achievement) -> bool {
+        if self.is_unlocked(&achievement) {
+            return false;
+        }
+
+        self.unlocked.push(achievement);
+        true
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        self.unlocked.iter()
+            .map(|achievement| achievement.get_modifiers(job_archetype))
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+}
+
+/// Achievements whose goal is reached but that aren't unlocked yet.
+pub fn newly_completed(state: &GameState) -> Vec<Achievement> {
+    Achievement::iter()
+        .filter(|achievement| !state.achievements.is_unlocked(achievement))
+        .filter(|achievement| {
+            let (current, required) = achievement.get_progress(state);
+            current >= required
+        })
+        .collect()
+}
+
+pub fn build_achievements_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let row_height = 64.0;
+    let bar_width = 300.0;
+
+    elements.push(UiElement::Text {
+        content: format!("Achievements {} / {}", state.achievements.unlocked.len(), Achievement::iter().count()),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    for (i, achievement) in Achievement::iter().enumerate() {
+        let x = rect.x + padding;
+        let y = rect.y + padding + 50.0 + i as f32 * row_height;
+        let unlocked = state.achievements.is_unlocked(&achievement);
+        let (current, required) = achievement.get_progress(state);
+
+        elements.push(UiElement::Text {
+            content: achievement.get_name().to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 18.0,
+            font_size: 18.0,
+            color: if unlocked { palette::TEXT.get_color() } else { palette::BORDER.get_color() },
+        });
+
+        let description = match achievement.get_reward_description() {
+            Some(reward) => format!("{} - {}", achievement.get_description(), reward),
+            None => achievement.get_description(),
+        };
+
+        elements.push(UiElement::Text {
+            content: description,
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 40.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        elements.push(UiElement::ProgressBar {
+            x: rect.x + rect.w - padding - bar_width - 120.0,
+            y: y + 8.0,
+            width: bar_width,
+            height: 14.0,
+            progress: if unlocked { 1.0 } else { (current as f64 / required as f64).min(1.0) },
+            background_color: palette::BAR_BACKGROUND.get_color(),
+            foreground_color: palette::PROGRESS_COLOR.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::Text {
+            content: if unlocked { "Unlocked".to_string() } else { format!("{} / {}", pretty_number(current), pretty_number(required)) },
+            font: assets.fonts.mono.clone(),
+            x: rect.x + rect.w - padding - 110.0,
+            y: y + 20.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    elements
+}
+
+pub fn unlocked_toast(achievement: &Achievement) -> Toast {
+    Toast {
+        text: format!("Achievement unlocked: {}", achievement.get_name()),
+        remaining_seconds: TOAST_SECONDS,
+    }
+}
@@ -26,6 +26,7 @@ pub enum AssetId {
 
     // Icons
     LockIcon,
+    WoodBurner,
 
     // Skill Icons
     LumberingIcon,
@@ -33,6 +34,7 @@ pub enum AssetId {
     HuntingIcon,
     HerbalismIcon,
     CookingIcon,
+    WoodworkingIcon,
 
     // Animations
     WoodAnim1,
@@ -49,16 +51,27 @@ pub enum AssetId {
     HuntingAnim2,
     SmithingAnim1,
     SmithingAnim2,
+    WoodworkingAnim1,
+    WoodworkingAnim2,
 
     // Icons and Items
     // Wood
     Kindlewood,
     Craftwood,
     Graintree,
+    Plank,
+    Beam,
+    Crate,
 
     // Ores
     IronOre,
 
+    // Tools
+    Axe,
+    Pickaxe,
+    Knife,
+    Mortar,
+
     MeatGame,
     Coin,
     BagOfCoins,
@@ -68,6 +81,17 @@ pub enum AssetId {
     Tree,
     Deer,
     ManaPotion,
+
+    // Buildings
+    Hut1,
+    Hut2,
+
+    // Runes
+    RuneBlank,
+    RuneTime,
+    RuneTime2,
+    RuneHeart,
+    RuneHeart2,
 }
 
 impl AssetId {
@@ -79,11 +103,13 @@ impl AssetId {
         match self {
             AssetId::BackgroundParchment => "chatgpt/parchment.png",
             AssetId::LockIcon => "chatgpt/icons/lock.png",
+            AssetId::WoodBurner => "wood_burner.png",
             AssetId::LumberingIcon => "chatgpt/skills/woodcutting.png",
             AssetId::MiningIcon => "chatgpt/skills/mining.png",
             AssetId::HuntingIcon => "chatgpt/skills/hunting.png",
             AssetId::HerbalismIcon => "chatgpt/skills/herbalism.png",
             AssetId::CookingIcon => "chatgpt/skills/cooking.png",
+            AssetId::WoodworkingIcon => "woodworking.png",
             AssetId::WoodAnim1 => "ChopChop_1_.png",
             AssetId::WoodAnim2 => "chop2_lanczos.png",
             AssetId::CookingAnim1 => "pan_1.png",
@@ -98,10 +124,19 @@ impl AssetId {
             AssetId::HuntingAnim2 => "PewPew_2.png",
             AssetId::SmithingAnim1 => "BomBom_1.png",
             AssetId::SmithingAnim2 => "BomBom_2.png",
+            AssetId::WoodworkingAnim1 => "WoodChop_1.png",
+            AssetId::WoodworkingAnim2 => "WoodChop_2.png",
             AssetId::Kindlewood => "chatgpt/kindlewood.png",
             AssetId::Craftwood => "chatgpt/craftwood.png",
             AssetId::Graintree => "chatgpt/graintree.png",
+            AssetId::Plank => "chatgpt/wood.png",
+            AssetId::Beam => "beam.png",
+            AssetId::Crate => "crate.png",
             AssetId::IronOre => "chatgpt/iron_ore.png",
+            AssetId::Axe => "axe.png",
+            AssetId::Pickaxe => "pickaxe.png",
+            AssetId::Knife => "knife.png",
+            AssetId::Mortar => "mortar.png",
             AssetId::MeatGame => "chatgpt/game.png",
             AssetId::Coin => "coin.png",
             AssetId::BagOfCoins => "chatgpt/bag_of_coins.png",
@@ -111,6 +146,13 @@ impl AssetId {
             AssetId::Tree => "chatgpt/tree.png",
             AssetId::Deer => "chatgpt/deer.png",
             AssetId::ManaPotion => "chatgpt/mana_potion.png",
+            AssetId::Hut1 => "hut1.png",
+            AssetId::Hut2 => "hut2.png",
+            AssetId::RuneBlank => "rune_blank.png",
+            AssetId::RuneTime => "rune_time.png",
+            AssetId::RuneTime2 => "rune_time2.png",
+            AssetId::RuneHeart => "rune_heart.png",
+            AssetId::RuneHeart2 => "rune_heart2.png",
             AssetId::ParchmentFrame => "chatgpt/ninepatch/frame.png",
         }
     }
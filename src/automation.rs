@@ -0,0 +1,323 @@
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{GameState, Intent, Item, UiRect};
+use crate::job::{JobArchetype, JobInstance, JobParameters};
+use crate::job_slot::JobSlotState;
+use crate::palette;
+use crate::skill::SkillArchetype;
+use strum::IntoEnumIterator;
+
+pub const MAX_RULES: usize = 10;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleCondition {
+    ItemBelow(Item, i64),
+    ItemAtLeast(Item, i64),
+    SkillLevelAtLeast(SkillArchetype, i64),
+    HyperCharged(usize),
+}
+
+impl RuleCondition {
+    pub fn is_met(&self, state: &GameState) -> bool {
+        match self {
+            RuleCondition::ItemBelow(item, amount) => state.inventory.get_item_amount(item) < *amount,
+            RuleCondition::ItemAtLeast(item, amount) => state.inventory.get_item_amount(item) >= *amount,
+            RuleCondition::SkillLevelAtLeast(skill, level) => {
+                state.skill_archetype_instances.get_skill_by_type(skill).actions_counter.level >= *level
+            }
+            RuleCondition::HyperCharged(index) => match state.job_slots.get(*index).map(|slot| &slot.state) {
+                Some(JobSlotState::RunningJob(job_instance)) => {
+                    job_instance.hyper_mode.has_enough_actions() && !job_instance.hyper_mode.is_enabled
+                }
+                _ => false,
+            },
+        }
+    }
+
+    pub fn describe_kind(&self) -> &str {
+        match self {
+            RuleCondition::ItemBelow(..) => "If item <",
+            RuleCondition::ItemAtLeast(..) => "If item >=",
+            RuleCondition::SkillLevelAtLeast(..) => "If level >=",
+            RuleCondition::HyperCharged(_) => "If hyper ready",
+        }
+    }
+
+    pub fn describe_subject(&self) -> String {
+        match self {
+            RuleCondition::ItemBelow(item, _) | RuleCondition::ItemAtLeast(item, _) => item.get_name(),
+            RuleCondition::SkillLevelAtLeast(skill, _) => skill.get_name().to_string(),
+            RuleCondition::HyperCharged(index) => format!("Slot {}", index + 1),
+        }
+    }
+
+    pub fn threshold(&self) -> Option<i64> {
+        match self {
+            RuleCondition::ItemBelow(_, amount) | RuleCondition::ItemAtLeast(_, amount) => Some(*amount),
+            RuleCondition::SkillLevelAtLeast(_, level) => Some(*level),
+            RuleCondition::HyperCharged(_) => None,
+        }
+    }
+
+    pub fn cycle_kind(&self) -> RuleCondition {
+        match self {
+            RuleCondition::ItemBelow(item, amount) => RuleCondition::ItemAtLeast(*item, *amount),
+            RuleCondition::ItemAtLeast(..) => RuleCondition::SkillLevelAtLeast(SkillArchetype::Lumbering, 10),
+            RuleCondition::SkillLevelAtLeast(..) => RuleCondition::HyperCharged(0),
+            RuleCondition::HyperCharged(_) => RuleCondition::ItemBelow(Item::Meat, 20),
+        }
+    }
+
+    pub fn cycle_subject(&self, slot_count: usize) -> RuleCondition {
+        match self {
+            RuleCondition::ItemBelow(item, amount) => RuleCondition::ItemBelow(cycle(&Item::all(), item), *amount),
+            RuleCondition::ItemAtLeast(item, amount) => RuleCondition::ItemAtLeast(cycle(&Item::all(), item), *amount),
+            RuleCondition::SkillLevelAtLeast(skill, level) => {
+                let skills: Vec<SkillArchetype> = SkillArchetype::iter().collect();
+                RuleCondition::SkillLevelAtLeast(cycle(&skills, skill), *level)
+            }
+            RuleCondition::HyperCharged(index) => RuleCondition::HyperCharged((index + 1) % slot_count),
+        }
+    }
+
+    pub fn change_threshold(&self, steps: i64) -> RuleCondition {
+        match self {
+            RuleCondition::ItemBelow(item, amount) => RuleCondition::ItemBelow(*item, (amount + steps * 10).max(0)),
+            RuleCondition::ItemAtLeast(item, amount) => RuleCondition::ItemAtLeast(*item, (amount + steps * 10).max(0)),
+            RuleCondition::SkillLevelAtLeast(skill, level) => RuleCondition::SkillLevelAtLeast(skill.clone(), (level + steps).max(1)),
+            RuleCondition::HyperCharged(index) => RuleCondition::HyperCharged(*index),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum RuleAction {
+    SwitchSlot(usize, JobArchetype),
+    EnableHyper(usize),
+    PauseSlot(usize),
+    ResumeSlot(usize),
+}
+
+impl RuleAction {
+    pub fn slot_index(&self) -> usize {
+        match self {
+            RuleAction::SwitchSlot(index, _)
+            | RuleAction::EnableHyper(index)
+            | RuleAction::PauseSlot(index)
+            | RuleAction::ResumeSlot(index) => *index,
+        }
+    }
+
+    /// The intent that carries out the action, or None if the slot is already in the desired state.
+    /// This keeps a rule whose condition stays true from restarting the same job every tick.
+    pub fn to_intent(&self, state: &GameState) -> Option<Intent> {
+        let slot_state = &state.job_slots.get(self.slot_index())?.state;
+
+        match (self, slot_state) {
+            (RuleAction::SwitchSlot(_, job_archetype), JobSlotState::RunningJob(job_instance))
+                if job_instance.job_archetype == *job_archetype => None,
+            // locked slots stay locked, and a slot the player is picking a job for isn't taken away
+            (RuleAction::SwitchSlot(..), JobSlotState::Locked | JobSlotState::PickingCategory | JobSlotState::PickingSkill(_) | JobSlotState::PickingProduct(_)) => None,
+            (RuleAction::SwitchSlot(index, job_archetype), _) => Some(Intent::ChangeJobSlotState(
+                *index,
+                JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: *job_archetype })),
+            )),
+            (RuleAction::EnableHyper(index), JobSlotState::RunningJob(job_instance))
+                if job_instance.hyper_mode.has_enough_actions() && !job_instance.hyper_mode.is_enabled => {
+                Some(Intent::EnableHyperMode(*index))
+            }
+            (RuleAction::PauseSlot(index), JobSlotState::RunningJob(job_instance)) if job_instance.running => {
+                Some(Intent::ToggleJob(*index))
+            }
+            (RuleAction::ResumeSlot(index), JobSlotState::RunningJob(job_instance)) if !job_instance.running => {
+                Some(Intent::ToggleJob(*index))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn describe_kind(&self) -> &str {
+        match self {
+            RuleAction::SwitchSlot(..) => "Switch",
+            RuleAction::EnableHyper(_) => "Enable hyper",
+            RuleAction::PauseSlot(_) => "Pause",
+            RuleAction::ResumeSlot(_) => "Resume",
+        }
+    }
+
+    pub fn cycle_kind(&self) -> RuleAction {
+        let index = self.slot_index();
+
+        match self {
+            RuleAction::SwitchSlot(..) => RuleAction::EnableHyper(index),
+            RuleAction::EnableHyper(_) => RuleAction::PauseSlot(index),
+            RuleAction::PauseSlot(_) => RuleAction::ResumeSlot(index),
+            RuleAction::ResumeSlot(_) => RuleAction::SwitchSlot(index, JobArchetype::all()[0]),
+        }
+    }
+
+    pub fn cycle_slot(&self, slot_count: usize) -> RuleAction {
+        let index = (self.slot_index() + 1) % slot_count;
+
+        match self {
+            RuleAction::SwitchSlot(_, job_archetype) => RuleAction::SwitchSlot(index, *job_archetype),
+            RuleAction::EnableHyper(_) => RuleAction::EnableHyper(index),
+            RuleAction::PauseSlot(_) => RuleAction::PauseSlot(index),
+            RuleAction::ResumeSlot(_) => RuleAction::ResumeSlot(index),
+        }
+    }
+
+    pub fn cycle_job(&self) -> RuleAction {
+        match self {
+            RuleAction::SwitchSlot(index, job_archetype) => RuleAction::SwitchSlot(*index, cycle(&JobArchetype::all(), job_archetype)),
+            other => other.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rule {
+    pub enabled: bool,
+    pub condition: RuleCondition,
+    pub action: RuleAction,
+}
+
+impl Rule {
+    pub fn new() -> Self {
+        Self {
+            enabled: true,
+            condition: RuleCondition::ItemBelow(Item::Meat, 20),
+            action: RuleAction::SwitchSlot(0, JobArchetype::all()[0]),
+        }
+    }
+}
+
+/// Player defined rules that are checked every tick and turned into intents,
+/// so automation goes through the same path as manual play.
+pub struct Automation {
+    pub rules: Vec<Rule>,
+}
+
+impl Automation {
+    pub fn new() -> Self {
+        Self { rules: vec![] }
+    }
+
+    pub fn intents(&self, state: &GameState) -> Vec<Intent> {
+        self.rules.iter()
+            .filter(|rule| rule.enabled && rule.condition.is_met(state))
+            .filter_map(|rule| rule.action.to_intent(state))
+            .collect()
+    }
+}
+
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T) -> T {
+    let position = values.iter().position(|value| value == current).unwrap_or(0);
+    values[(position + 1) % values.len()].clone()
+}
+
+pub fn build_automation_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let row_height = 32.0;
+    let row_spacing = 12.0;
+    let slot_count = state.job_slots.len();
+
+    elements.push(UiElement::Text {
+        content: "Automation Rules".to_string(),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    if state.automation.rules.len() < state.research.rule_limit() {
+        elements.push(rule_button(assets, UiRect::new(rect.x + rect.w - padding - 120.0, rect.y + padding, 120.0, row_height), "Add Rule", Intent::AddRule(Rule::new())));
+    }
+
+    for (i, rule) in state.automation.rules.iter().enumerate() {
+        let y = rect.y + padding + 50.0 + i as f32 * (row_height + row_spacing);
+        let x = rect.x + padding;
+        let update = |rule: Rule| Intent::UpdateRule(i, rule);
+
+        elements.push(rule_button(assets, UiRect::new(x, y, 60.0, row_height),
+            if rule.enabled { "On" } else { "Off" },
+            update(Rule { enabled: !rule.enabled, ..rule.clone() })));
+
+        elements.push(rule_button(assets, UiRect::new(x + 70.0, y, 130.0, row_height),
+            rule.condition.describe_kind(),
+            update(Rule { condition: rule.condition.cycle_kind(), ..rule.clone() })));
+
+        elements.push(rule_button(assets, UiRect::new(x + 210.0, y, 140.0, row_height),
+            &rule.condition.describe_subject(),
+            update(Rule { condition: rule.condition.cycle_subject(slot_count), ..rule.clone() })));
+
+        if let Some(threshold) = rule.condition.threshold() {
+            elements.push(UiElement::Text {
+                content: threshold.to_string(),
+                font: assets.fonts.mono.clone(),
+                x: x + 365.0,
+                y: y + row_height / 2.0 + 6.0,
+                font_size: 18.0,
+                color: palette::TEXT.get_color(),
+            });
+
+            elements.push(rule_button(assets, UiRect::new(x + 420.0, y, 30.0, row_height), "-",
+                update(Rule { condition: rule.condition.change_threshold(-1), ..rule.clone() })));
+            elements.push(rule_button(assets, UiRect::new(x + 455.0, y, 30.0, row_height), "+",
+                update(Rule { condition: rule.condition.change_threshold(1), ..rule.clone() })));
+        }
+
+        elements.push(UiElement::Text {
+            content: "then".to_string(),
+            font: assets.fonts.text.clone(),
+            x: x + 500.0,
+            y: y + row_height / 2.0 + 6.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(rule_button(assets, UiRect::new(x + 545.0, y, 130.0, row_height),
+            rule.action.describe_kind(),
+            update(Rule { action: rule.action.cycle_kind(), ..rule.clone() })));
+
+        elements.push(rule_button(assets, UiRect::new(x + 685.0, y, 80.0, row_height),
+            &format!("Slot {}", rule.action.slot_index() + 1),
+            update(Rule { action: rule.action.cycle_slot(slot_count), ..rule.clone() })));
+
+        if let RuleAction::SwitchSlot(_, job_archetype) = &rule.action {
+            elements.push(rule_button(assets, UiRect::new(x + 775.0, y, 140.0, row_height),
+                &job_archetype.get_name(),
+                update(Rule { action: rule.action.cycle_job(), ..rule.clone() })));
+        }
+
+        elements.push(rule_button(assets, UiRect::new(x + 925.0, y, 30.0, row_height), "x", Intent::RemoveRule(i)));
+    }
+
+    elements
+}
+
+fn rule_button(assets: &Assets, rectangle: UiRect, text: &str, intent: Intent) -> UiElement {
+    UiElement::RectButton {
+        rectangle,
+        font_size: 14.0,
+        font: assets.fonts.text.clone(),
+        text: text.to_string(),
+        background_color: palette::BUTTON_BACKGROUND.get_color(),
+        text_color: palette::BUTTON_TEXT.get_color(),
+        intent,
+        parent_clip: None,
+        border_style: BorderStyle::Solid,
+    }
+}
@@ -0,0 +1,125 @@
+use crate::game::{GameState, Item};
+use crate::job::{JobArchetype, JobModifiers};
+use crate::job_slot::JobSlotState;
+use crate::skill::SkillCategory;
+use strum_macros::EnumIter;
+
+pub const SANDWICH_BUFF_SECONDS: f64 = 60.0;
+pub const MANA_POTION_HYPER_SECONDS: f64 = 10.0;
+
+/// Mana potions extend an active hyper mode, but never beyond this much remaining time.
+pub const MAX_HYPER_SECONDS: f64 = 60.0;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BuffKind {
+    WellFed,
+}
+
+impl BuffKind {
+    pub fn get_name(&self) -> &str {
+        match self {
+            BuffKind::WellFed => "Well Fed",
+        }
+    }
+
+    /// Using the same consumable again extends the buff, but never beyond this.
+    pub fn max_duration(&self) -> f64 {
+        match self {
+            BuffKind::WellFed => 10.0 * 60.0,
+        }
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        match self {
+            BuffKind::WellFed if SkillCategory::Gathering.get_skill_archetypes().contains(&job_archetype.get_skill_type()) => {
+                JobModifiers { speed_multiplier: 1.25, ..JobModifiers::none() }
+            }
+            _ => JobModifiers::none(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Buff {
+    pub kind: BuffKind,
+    pub remaining_seconds: f64,
+}
+
+/// Timed effects from consumables. Different kinds stack with each other,
+/// the same kind only adds to the remaining time.
+pub struct Buffs {
+    pub active: Vec<Buff>,
+}
+
+impl Buffs {
+    pub fn new() -> Self {
+        Self { active: vec![] }
+    }
+
+    pub fn apply(&mut self, kind: BuffKind, seconds: f64) {
+        match self.active.iter_mut().find(|buff| buff.kind == kind) {
+            Some(buff) => buff.remaining_seconds = (buff.remaining_seconds + seconds).min(kind.max_duration()),
+            None => self.active.push(Buff { kind, remaining_seconds: seconds.min(kind.max_duration()) }),
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for buff in &mut self.active {
+            buff.remaining_seconds -= dt as f64;
+        }
+
+        self.active.retain(|buff| buff.remaining_seconds > 0.0);
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        self.active.iter()
+            .map(|buff| buff.kind.get_modifiers(job_archetype))
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+}
+
+/// Uses up one consumable. Mana potions need the slot whose hyper mode they charge.
+/// Returns false and keeps the item if it would have no effect.
+pub fn use_consumable(state: &mut GameState, item: Item, slot_index: Option<usize>) -> bool {
+    if state.inventory.get_item_amount(&item) < 1 {
+        return false;
+    }
+
+    let used = match item {
+        Item::Sandwich => {
+            state.buffs.apply(BuffKind::WellFed, SANDWICH_BUFF_SECONDS);
+            true
+        }
+        Item::ManaPotion => {
+            let slot_state = slot_index.and_then(|index| state.job_slots.get_mut(index)).map(|slot| &mut slot.state);
+
+            match slot_state {
+                Some(JobSlotState::RunningJob(job_instance)) => {
+                    let hyper_mode = &mut job_instance.hyper_mode;
+
+                    if hyper_mode.is_enabled {
+                        let min_accumulator = hyper_mode.hyper_duration_seconds - MAX_HYPER_SECONDS;
+                        let extended = (hyper_mode.hyper_time_accumulator - MANA_POTION_HYPER_SECONDS).max(min_accumulator);
+                        let used = extended < hyper_mode.hyper_time_accumulator;
+
+                        hyper_mode.hyper_time_accumulator = extended;
+                        used
+                    } else if !hyper_mode.has_enough_actions() {
+                        hyper_mode.actions_counter = hyper_mode.hyper_actions_cost;
+                        true
+                    } else {
+                        false
+                    }
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+
+    if used {
+        state.inventory.add_item(item, -1);
+    }
+
+    used
+}
@@ -0,0 +1,517 @@
+use crate::toasts::{Toast, TOAST_SECONDS};
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::counts_actions::CountsActions;
+use crate::draw::{BorderStyle, UiElement};
+use crate::equipment::Tool;
+use crate::expeditions::{roll_loot, LootEntry};
+use crate::game::{GameState, Intent, Item, UiRect};
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::runes::Rune;
+use std::collections::VecDeque;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+pub const PLAYER_ATTACK_SECONDS: f64 = 2.0;
+
+/// Food is eaten after a hit that leaves less than this share of health.
+pub const EAT_BELOW_HEALTH: f64 = 0.5;
+
+/// Health regained per second while not fighting.
+pub const REST_HP_PER_SECOND: f64 = 1.0;
+
+/// Firebolt uses up a mana potion for this much of a normal hit.
+pub const FIREBOLT_DAMAGE_MULTIPLIER: f64 = 4.0;
+pub const FIREBOLT_COOLDOWN_SECONDS: f64 = 15.0;
+
+pub const LOG_LENGTH: usize = 14;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Enemy {
+    Wolf,
+    Bandit,
+    Troll,
+}
+
+impl Enemy {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Enemy::Wolf => "Wolf",
+            Enemy::Bandit => "Bandit",
+            Enemy::Troll => "Troll",
+        }
+    }
+
+    pub fn get_attack_verb(&self) -> &str {
+        match self {
+            Enemy::Wolf => "bites",
+            Enemy::Bandit => "stabs",
+            Enemy::Troll => "smashes",
+        }
+    }
+
+    pub fn get_max_hp(&self) -> f64 {
+        match self {
+            Enemy::Wolf => 30.0,
+            Enemy::Bandit => 80.0,
+            Enemy::Troll => 250.0,
+        }
+    }
+
+    pub fn get_damage(&self) -> f64 {
+        match self {
+            Enemy::Wolf => 4.0,
+            Enemy::Bandit => 8.0,
+            Enemy::Troll => 18.0,
+        }
+    }
+
+    pub fn get_attack_seconds(&self) -> f64 {
+        match self {
+            Enemy::Wolf => 2.5,
+            Enemy::Bandit => 2.0,
+            Enemy::Troll => 3.0,
+        }
+    }
+
+    /// Combat level needed to pick a fight with it.
+    pub fn get_required_level(&self) -> i64 {
+        match self {
+            Enemy::Wolf => 1,
+            Enemy::Bandit => 5,
+            Enemy::Troll => 10,
+        }
+    }
+
+    /// Combat actions a kill is worth.
+    pub fn get_experience(&self) -> i64 {
+        match self {
+            Enemy::Wolf => 3,
+            Enemy::Bandit => 8,
+            Enemy::Troll => 25,
+        }
+    }
+
+    pub fn get_drops(&self) -> Vec<LootEntry> {
+        match self {
+            Enemy::Wolf => vec![
+                LootEntry::new(Item::Meat, 1, 3, 1.0),
+            ],
+            Enemy::Bandit => vec![
+                LootEntry::new(Item::Coin, 10, 30, 1.0),
+                LootEntry::new(Item::IronBar, 1, 2, 0.3),
+                LootEntry::new(Item::Tool(Tool::Knife), 1, 1, 0.05),
+            ],
+            Enemy::Troll => vec![
+                LootEntry::new(Item::IronOre, 10, 20, 0.8),
+                LootEntry::new(Item::Rune(Rune::Heart), 1, 1, 0.1),
+                LootEntry::new(Item::Rune(Rune::Time), 1, 1, 0.1),
+            ],
+        }
+    }
+}
+
+/// Health food gives back when eaten during a fight.
+pub fn food_healing(item: &Item) -> Option<f64> {
+    match item {
+        Item::Meat => Some(15.0),
+        Item::Sandwich => Some(40.0),
+        _ => None,
+    }
+}
+
+/// The enemy currently fought. A new one of the same kind steps up when it falls.
+pub struct Fight {
+    pub enemy: Enemy,
+    pub enemy_hp: f64,
+    pub attack_timer: f64,
+    pub enemy_attack_timer: f64,
+}
+
+impl Fight {
+    pub fn new(enemy: Enemy) -> Self {
+        Self {
+            enemy,
+            enemy_hp: enemy.get_max_hp(),
+            attack_timer: PLAYER_ATTACK_SECONDS,
+            enemy_attack_timer: enemy.get_attack_seconds(),
+        }
+    }
+}
+
+pub struct Combat {
+    pub experience: CountsActions,
+    pub hp: f64,
+    pub fight: Option<Fight>,
+    pub use_abilities: bool,
+    pub ability_cooldown: f64,
+    pub kills: i64,
+    pub log: VecDeque<String>,
+}
+
+impl Combat {
+    pub fn new() -> Self {
+        Self {
+            experience: CountsActions::new(Self::actions_to_level, 10),
+            hp: 50.0,
+            fight: None,
+            use_abilities: true,
+            ability_cooldown: 0.0,
+            kills: 0,
+            log: VecDeque::new(),
+        }
+    }
+
+    fn actions_to_level(level: i64) -> i64 {
+        5 * level * level
+    }
+
+    pub fn max_hp(&self) -> f64 {
+        50.0 + 10.0 * (self.experience.level - 1) as f64
+    }
+
+    pub fn damage(&self) -> f64 {
+        6.0 + 2.0 * (self.experience.level - 1) as f64
+    }
+
+    pub fn add_log(&mut self, line: String) {
+        self.log.push_back(line);
+
+        while self.log.len() > LOG_LENGTH {
+            self.log.pop_front();
+        }
+    }
+}
+
+/// Needs the level for the enemy and at least half of the health back.
+pub fn can_fight(state: &GameState, enemy: &Enemy) -> bool {
+    let combat = &state.combat;
+
+    combat.experience.level >= enemy.get_required_level() && combat.hp >= combat.max_hp() * 0.5
+}
+
+pub fn start_fight(state: &mut GameState, enemy: Enemy) -> bool {
+    if !can_fight(state, &enemy) {
+        return false;
+    }
+
+    state.combat.fight = Some(Fight::new(enemy));
+    state.combat.add_log(format!("You go looking for a {}", enemy.get_name()));
+
+    true
+}
+
+pub fn stop_fight(state: &mut GameState) {
+    if let Some(fight) = state.combat.fight.take() {
+        state.combat.add_log(format!("You back away from the {}", fight.enemy.get_name()));
+    }
+}
+
+/// Plays the fight out hit by hit, so long steps from time skips land on the same result as short ones.
+pub fn update_combat(state: &mut GameState, dt: f32) {
+    let dt = dt as f64;
+
+    let Some(mut fight) = state.combat.fight.take() else {
+        state.combat.ability_cooldown = (state.combat.ability_cooldown - dt).max(0.0);
+        state.combat.hp = (state.combat.hp + REST_HP_PER_SECOND * dt).min(state.combat.max_hp());
+        return;
+    };
+
+    let mut remaining = dt;
+
+    while remaining > 0.0 {
+        let step = remaining.min(fight.attack_timer).min(fight.enemy_attack_timer);
+        remaining -= step;
+        fight.attack_timer -= step;
+        fight.enemy_attack_timer -= step;
+        state.combat.ability_cooldown = (state.combat.ability_cooldown - step).max(0.0);
+
+        if fight.attack_timer <= 0.0 {
+            fight.attack_timer += PLAYER_ATTACK_SECONDS;
+            player_attack(state, &mut fight);
+
+            if fight.enemy_hp <= 0.0 {
+                defeat_enemy(state, fight.enemy);
+                fight = Fight::new(fight.enemy);
+                continue;
+            }
+        }
+
+        if fight.enemy_attack_timer <= 0.0 {
+            fight.enemy_attack_timer += fight.enemy.get_attack_seconds();
+            enemy_attack(state, &fight);
+
+            if state.combat.hp <= 0.0 {
+                state.combat.hp = 0.0;
+                state.combat.add_log(format!("The {} beats you, you limp home to rest", fight.enemy.get_name()));
+                state.toasts.push(Toast {
+                    text: format!("Beaten by a {}", fight.enemy.get_name()),
+                    remaining_seconds: TOAST_SECONDS,
+                });
+                return;
+            }
+        }
+    }
+
+    state.combat.fight = Some(fight);
+}
+
+fn player_attack(state: &mut GameState, fight: &mut Fight) {
+    let name = fight.enemy.get_name();
+    let roll = state.rng.range(0.8, 1.2);
+    let firebolt = state.combat.use_abilities
+        && state.combat.ability_cooldown <= 0.0
+        && state.inventory.get_item_amount(&Item::ManaPotion) > 0;
+
+    if firebolt {
+        let damage = (state.combat.damage() * FIREBOLT_DAMAGE_MULTIPLIER * roll).round();
+        state.inventory.add_item(Item::ManaPotion, -1);
+        state.combat.ability_cooldown = FIREBOLT_COOLDOWN_SECONDS;
+        fight.enemy_hp -= damage;
+        state.combat.add_log(format!("Firebolt scorches the {} for {}", name, damage));
+    } else {
+        let damage = (state.combat.damage() * roll).round();
+        fight.enemy_hp -= damage;
+        state.combat.add_log(format!("You hit the {} for {}", name, damage));
+    }
+}
+
+fn enemy_attack(state: &mut GameState, fight: &Fight) {
+    let damage = (fight.enemy.get_damage() * state.rng.range(0.8, 1.2)).round();
+    state.combat.hp -= damage;
+    state.combat.add_log(format!("The {} {} you for {}", fight.enemy.get_name(), fight.enemy.get_attack_verb(), damage));
+
+    if state.combat.hp > 0.0 && state.combat.hp < state.combat.max_hp() * EAT_BELOW_HEALTH {
+        eat_food(state);
+    }
+}
+
+/// Eats whatever heals the most without going to waste, or the smallest bite there is.
+fn eat_food(state: &mut GameState) {
+    let missing = state.combat.max_hp() - state.combat.hp;
+    let mut foods: Vec<(Item, f64)> = [Item::Sandwich, Item::Meat].into_iter()
+        .filter(|item| state.inventory.get_item_amount(item) > 0)
+        .filter_map(|item| food_healing(&item).map(|healing| (item, healing)))
+        .collect();
+
+    foods.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let Some((item, healing)) = foods.iter().find(|(_, healing)| *healing <= missing).or(foods.last()).copied() else {
+        return;
+    };
+
+    state.inventory.add_item(item, -1);
+    state.combat.hp = (state.combat.hp + healing).min(state.combat.max_hp());
+    state.combat.add_log(format!("You eat a {} (+{})", item.get_name(), healing));
+}
+
+fn defeat_enemy(state: &mut GameState, enemy: Enemy) {
+    let drops = roll_loot(&enemy.get_drops(), &mut state.rng);
+
+    for (item, amount) in &drops {
+        let room = state.village.storage_room(item, state.inventory.get_item_amount(item));
+        state.inventory.add_item(*item, (*amount).min(room));
+    }
+
+    let level_before = state.combat.experience.level;
+    for _ in 0..enemy.get_experience() {
+        state.combat.experience.increment_actions();
+    }
+
+    state.combat.kills += 1;
+
+    let found = drops.iter()
+        .map(|(item, amount)| format!("{} {}", amount, item.get_name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    state.combat.add_log(format!("The {} falls{}", enemy.get_name(), if found.is_empty() { String::new() } else { format!(", dropping {}", found) }));
+
+    if state.combat.experience.level > level_before {
+        state.toasts.push(Toast {
+            text: format!("Combat level {}", state.combat.experience.level),
+            remaining_seconds: TOAST_SECONDS,
+        });
+    }
+}
+
+pub fn build_combat_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let column_width = (rect.w - padding * 4.0) / 3.0;
+    let combat = &state.combat;
+
+    elements.push(UiElement::Text {
+        content: format!("Combat - level {}, {} kills", combat.experience.level, combat.kills),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    // the player, their supplies and the running fight
+    let x = rect.x + padding;
+    let mut y = rect.y + padding + 60.0;
+
+    elements.push(UiElement::Text {
+        content: format!("Health {:.0} / {:.0}", combat.hp, combat.max_hp()),
+        font: assets.fonts.text.clone(),
+        x,
+        y: y + 14.0,
+        font_size: 14.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    elements.push(UiElement::ProgressBar {
+        x,
+        y: y + 22.0,
+        width: column_width,
+        height: 12.0,
+        progress: combat.hp / combat.max_hp(),
+        background_color: palette::BAR_BACKGROUND.get_color(),
+        foreground_color: PaletteC::Coral.get_color(),
+        border_style: BorderStyle::Solid,
+    });
+
+    y += 48.0;
+
+    let details = [
+        format!("{:.0} damage every {:.1}s", combat.damage(), PLAYER_ATTACK_SECONDS),
+        format!("Food: {} Sandwich, {} Meat", state.inventory.get_item_amount(&Item::Sandwich), state.inventory.get_item_amount(&Item::Meat)),
+        format!("Mana Potions: {}", state.inventory.get_item_amount(&Item::ManaPotion)),
+    ];
+
+    for line in details {
+        elements.push(UiElement::Text {
+            content: line,
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 14.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        y += 22.0;
+    }
+
+    y += 8.0;
+
+    elements.push(UiElement::RectButton {
+        rectangle: UiRect::new(x, y, column_width, 32.0),
+        font_size: 14.0,
+        font: assets.fonts.text_bold.clone(),
+        text: format!("Firebolt: {}", if combat.use_abilities { "on" } else { "off" }),
+        background_color: palette::BUTTON_BACKGROUND.get_color(),
+        text_color: palette::BUTTON_TEXT.get_color(),
+        intent: Intent::ToggleCombatAbilities,
+        parent_clip: None,
+        border_style: BorderStyle::Solid,
+    });
+
+    y += 48.0;
+
+    if let Some(fight) = &combat.fight {
+        elements.push(UiElement::Text {
+            content: format!("{} {:.0} / {:.0}", fight.enemy.get_name(), fight.enemy_hp.max(0.0), fight.enemy.get_max_hp()),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 14.0,
+            font_size: 14.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::ProgressBar {
+            x,
+            y: y + 22.0,
+            width: column_width,
+            height: 12.0,
+            progress: fight.enemy_hp.max(0.0) / fight.enemy.get_max_hp(),
+            background_color: palette::BAR_BACKGROUND.get_color(),
+            foreground_color: palette::PROGRESS_COLOR.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::RectButton {
+            rectangle: UiRect::new(x, y + 44.0, column_width, 32.0),
+            font_size: 14.0,
+            font: assets.fonts.text_bold.clone(),
+            text: "Retreat".to_string(),
+            background_color: palette::BUTTON_BACKGROUND.get_color(),
+            text_color: palette::BUTTON_TEXT.get_color(),
+            intent: Intent::StopFight,
+            parent_clip: None,
+            border_style: BorderStyle::Solid,
+        });
+    }
+
+    // enemies to pick from
+    let x = rect.x + padding * 2.0 + column_width;
+    let mut y = rect.y + padding + 60.0;
+
+    for enemy in Enemy::iter() {
+        let drops = enemy.get_drops().iter()
+            .map(|entry| format!("{} ({:.0}%)", entry.item.get_name(), entry.chance * 100.0))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        elements.push(UiElement::Text {
+            content: format!("{} - {:.0} hp, {:.0} damage, level {}", enemy.get_name(), enemy.get_max_hp(), enemy.get_damage(), enemy.get_required_level()),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 14.0,
+            font_size: 14.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::Text {
+            content: drops,
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 34.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        elements.push(UiElement::RectButton {
+            rectangle: UiRect::new(x, y + 44.0, column_width, 32.0),
+            font_size: 14.0,
+            font: assets.fonts.text_bold.clone(),
+            text: format!("Fight {}", enemy.get_name()),
+            background_color: if can_fight(state, &enemy) { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+            text_color: palette::BUTTON_TEXT.get_color(),
+            intent: Intent::StartFight(enemy),
+            parent_clip: None,
+            border_style: BorderStyle::Solid,
+        });
+
+        y += 96.0;
+    }
+
+    // encounter log, newest at the bottom
+    let x = rect.x + padding * 3.0 + column_width * 2.0;
+    let y = rect.y + padding + 60.0;
+
+    for (i, line) in combat.log.iter().enumerate() {
+        elements.push(UiElement::Text {
+            content: line.clone(),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 14.0 + i as f32 * 20.0,
+            font_size: 14.0,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    elements
+}
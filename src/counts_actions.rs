@@ -40,6 +40,16 @@ impl CountsActions {
         }
     }
 
+    /// Sets the counter to a previously saved state.
+    pub fn restore(&mut self, level: i64, actions_done_current_level: i64, actions_done_total: i64) {
+        self.level = level.max(1);
+        self.actions_done_current_level = actions_done_current_level;
+        self.actions_done_total = actions_done_total;
+        self.level_up_progress.set(
+            self.actions_done_current_level as f64 / self.actions_to_next_level() as f64
+        );
+    }
+
     pub fn level_up(&mut self) {
         self.level += 1;
         self.actions_done_current_level = 0;
@@ -1,5 +1,7 @@
+use crate::assets::Assets;
 use crate::game::{Intent, MouseInput, UiRect};
 use crate::palette;
+use crate::palette::PaletteC;
 use macroquad::color::{Color, WHITE};
 use macroquad::math::Vec2;
 use macroquad::prelude::{draw_rectangle, draw_text, draw_text_ex, draw_texture_ex, get_internal_gl, measure_text, DrawTextureParams, QuadGl, Rect, Texture2D};
@@ -204,6 +206,21 @@ fn is_mouse_down_on(element: &UiElement, mouse_input: &MouseInput) -> bool {
     }
 }
 
+/// Text button that turns coral while the player can't use it.
+pub fn button(assets: &Assets, rectangle: UiRect, text: &str, enabled: bool, intent: Intent, parent_clip: Option<(i32, i32, i32, i32)>) -> UiElement {
+    UiElement::RectButton {
+        rectangle,
+        font_size: 14.0,
+        font: assets.fonts.text_bold.clone(),
+        text: text.to_string(),
+        background_color: if enabled { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+        text_color: palette::BUTTON_TEXT.get_color(),
+        intent,
+        parent_clip,
+        border_style: BorderStyle::Solid,
+    }
+}
+
 pub fn number_pill(x: f32, y: f32, w: f32, h: f32, number: i64, text_color: Option<Color>, font: Font) -> Vec<UiElement> {
     pill(x, y, w, h, format!("{}", to_pill_number(number)).as_str(), text_color, font)
 }
@@ -0,0 +1,99 @@
+use crate::assets::{AssetId, Assets};
+use crate::game::{Inventory, Item};
+use crate::job::JobModifiers;
+use crate::skill::SkillArchetype;
+use macroquad::prelude::Texture2D;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+pub const TOOL_DURABILITY: i64 = 100;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Tool {
+    Axe,
+    Pickaxe,
+    Knife,
+    Mortar,
+}
+
+impl Tool {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Tool::Axe => "Axe",
+            Tool::Pickaxe => "Pickaxe",
+            Tool::Knife => "Knife",
+            Tool::Mortar => "Mortar",
+        }
+    }
+
+    pub fn get_texture(&self, assets: &Assets) -> Texture2D {
+        match self {
+            Tool::Axe => AssetId::Axe.get_texture(assets),
+            Tool::Pickaxe => AssetId::Pickaxe.get_texture(assets),
+            Tool::Knife => AssetId::Knife.get_texture(assets),
+            Tool::Mortar => AssetId::Mortar.get_texture(assets),
+        }
+    }
+
+    pub fn get_skill_type(&self) -> SkillArchetype {
+        match self {
+            Tool::Axe => SkillArchetype::Lumbering,
+            Tool::Pickaxe => SkillArchetype::Mining,
+            Tool::Knife => SkillArchetype::Hunting,
+            Tool::Mortar => SkillArchetype::Alchemy,
+        }
+    }
+
+    pub fn for_skill(skill_type: &SkillArchetype) -> Option<Tool> {
+        Tool::iter().find(|tool| tool.get_skill_type() == *skill_type)
+    }
+
+    /// Axes and pickaxes make their jobs faster, knives and mortars get more out of each action.
+    pub fn get_modifiers(&self) -> JobModifiers {
+        match self {
+            Tool::Axe | Tool::Pickaxe => JobModifiers { speed_multiplier: 1.25, ..JobModifiers::none() },
+            Tool::Knife | Tool::Mortar => JobModifiers { yield_multiplier: 1.5, ..JobModifiers::none() },
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct EquippedTool {
+    pub tool: Tool,
+    pub durability: i64,
+}
+
+/// Tools equipped per skill. Every action of the skill wears its tool down until it breaks.
+pub struct Equipment {
+    pub equipped: Vec<EquippedTool>,
+}
+
+impl Equipment {
+    pub fn new() -> Self {
+        Self { equipped: vec![] }
+    }
+
+    pub fn get_tool_for_skill(&self, skill_type: &SkillArchetype) -> Option<&EquippedTool> {
+        self.equipped.iter().find(|equipped| equipped.tool.get_skill_type() == *skill_type)
+    }
+
+    /// Takes the tool out of the inventory. A skill keeps its tool until it breaks, so nothing worn is thrown away.
+    pub fn equip(&mut self, tool: Tool, inventory: &mut Inventory) -> bool {
+        if inventory.get_item_amount(&Item::Tool(tool)) < 1 || self.get_tool_for_skill(&tool.get_skill_type()).is_some() {
+            return false;
+        }
+
+        inventory.add_item(Item::Tool(tool), -1);
+        self.equipped.push(EquippedTool { tool, durability: TOOL_DURABILITY });
+
+        true
+    }
+
+    pub fn wear(&mut self, skill_type: &SkillArchetype) {
+        for equipped in self.equipped.iter_mut().filter(|equipped| equipped.tool.get_skill_type() == *skill_type) {
+            equipped.durability -= 1;
+        }
+
+        self.equipped.retain(|equipped| equipped.durability > 0);
+    }
+}
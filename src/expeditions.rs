@@ -0,0 +1,375 @@
+use crate::toasts::{Toast, TOAST_SECONDS};
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{pretty_duration, GameState, Intent, Item, UiRect, WoodItem};
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::rng::Rng;
+use crate::runes::Rune;
+use crate::skill::SkillArchetype;
+use crate::workers::Worker;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// One possible find: the item, how many, and how likely it turns up at all.
+pub struct LootEntry {
+    pub item: Item,
+    pub min: i64,
+    pub max: i64,
+    pub chance: f64,
+}
+
+impl LootEntry {
+    pub fn new(item: Item, min: i64, max: i64, chance: f64) -> Self {
+        Self { item, min, max, chance }
+    }
+}
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Destination {
+    BerryHills,
+    OldQuarry,
+    AncientGrove,
+}
+
+impl Destination {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Destination::BerryHills => "Berry Hills",
+            Destination::OldQuarry => "Old Quarry",
+            Destination::AncientGrove => "Ancient Grove",
+        }
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        match self {
+            Destination::BerryHills => 10.0 * 60.0,
+            Destination::OldQuarry => 30.0 * 60.0,
+            Destination::AncientGrove => 2.0 * 60.0 * 60.0,
+        }
+    }
+
+    pub fn get_party_size(&self) -> usize {
+        match self {
+            Destination::BerryHills => 1,
+            Destination::OldQuarry => 2,
+            Destination::AncientGrove => 3,
+        }
+    }
+
+    /// Eaten on the way, paid when the party sets out.
+    pub fn get_supplies(&self) -> Vec<(Item, i64)> {
+        match self {
+            Destination::BerryHills => vec![(Item::Sandwich, 2)],
+            Destination::OldQuarry => vec![(Item::Sandwich, 5)],
+            Destination::AncientGrove => vec![(Item::Sandwich, 8), (Item::ManaPotion, 3)],
+        }
+    }
+
+    pub fn get_loot_table(&self) -> Vec<LootEntry> {
+        match self {
+            Destination::BerryHills => vec![
+                LootEntry::new(Item::Berry, 5, 15, 1.0),
+                LootEntry::new(Item::Herb, 3, 8, 0.6),
+                LootEntry::new(Item::ManaPotion, 1, 1, 0.1),
+            ],
+            Destination::OldQuarry => vec![
+                LootEntry::new(Item::IronOre, 20, 40, 1.0),
+                LootEntry::new(Item::IronBar, 3, 8, 0.5),
+                LootEntry::new(Item::Rune(Rune::Blank), 1, 1, 0.15),
+            ],
+            Destination::AncientGrove => vec![
+                LootEntry::new(Item::Wood(WoodItem::Craftwood), 40, 80, 1.0),
+                LootEntry::new(Item::Wood(WoodItem::Graintree), 20, 40, 0.5),
+                LootEntry::new(Item::Rune(Rune::Time), 1, 1, 0.2),
+                LootEntry::new(Item::Rune(Rune::Heart), 1, 1, 0.2),
+            ],
+        }
+    }
+
+    /// The skill that learns from the trip, and how many actions it is worth. Every member of the party gets the same.
+    pub fn get_experience(&self) -> (SkillArchetype, i64) {
+        match self {
+            Destination::BerryHills => (SkillArchetype::Foraging, 20),
+            Destination::OldQuarry => (SkillArchetype::Mining, 40),
+            Destination::AncientGrove => (SkillArchetype::Lumbering, 80),
+        }
+    }
+
+    pub fn roll_loot(&self, rng: &mut Rng) -> Vec<(Item, i64)> {
+        roll_loot(&self.get_loot_table(), rng)
+    }
+}
+
+/// Rolls every entry of a loot table on its own.
+pub fn roll_loot(table: &[LootEntry], rng: &mut Rng) -> Vec<(Item, i64)> {
+    let mut loot = vec![];
+
+    for entry in table {
+        if rng.chance(entry.chance) {
+            loot.push((entry.item, entry.min + rng.index((entry.max - entry.min + 1) as usize) as i64));
+        }
+    }
+
+    loot
+}
+
+/// A party on its way. The workers leave the village until they are back.
+pub struct Expedition {
+    pub destination: Destination,
+    pub party: Vec<Worker>,
+    pub remaining_seconds: f64,
+}
+
+impl Expedition {
+    pub fn progress(&self) -> f64 {
+        1.0 - self.remaining_seconds / self.destination.get_duration()
+    }
+}
+
+pub struct Expeditions {
+    pub active: Vec<Expedition>,
+}
+
+impl Expeditions {
+    pub fn new() -> Self {
+        Self {
+            active: vec![],
+        }
+    }
+
+    pub fn get_active(&self, destination: &Destination) -> Option<&Expedition> {
+        self.active.iter().find(|expedition| expedition.destination == *destination)
+    }
+
+    /// Moves the parties along. Returns the ones that came back.
+    pub fn update(&mut self, dt: f32) -> Vec<Expedition> {
+        for expedition in &mut self.active {
+            expedition.remaining_seconds -= dt as f64;
+        }
+
+        let (returned, away) = self.active.drain(..).partition(|expedition| expedition.remaining_seconds <= 0.0);
+        self.active = away;
+
+        returned
+    }
+}
+
+/// Workers that would go on the trip: idle ones first, then those working a slot.
+pub fn pick_party(state: &GameState, destination: &Destination) -> Vec<usize> {
+    let mut candidates: Vec<usize> = (0..state.workers.hired.len()).collect();
+    candidates.sort_by_key(|index| state.workers.hired[*index].assigned_slot.is_some());
+    candidates.truncate(destination.get_party_size());
+    candidates
+}
+
+pub fn can_start_expedition(state: &GameState, destination: &Destination) -> bool {
+    state.expeditions.get_active(destination).is_none()
+        && state.workers.hired.len() >= destination.get_party_size()
+        && destination.get_supplies().iter().all(|(item, amount)| state.inventory.get_item_amount(item) >= *amount)
+}
+
+/// Sends the workers with the given indices off. They stop working their slots until they return.
+pub fn start_expedition(state: &mut GameState, destination: Destination, party: &[usize]) -> bool {
+    let mut party = party.to_vec();
+    party.sort_unstable();
+    party.dedup();
+
+    if !can_start_expedition(state, &destination)
+        || party.len() != destination.get_party_size()
+        || party.iter().any(|index| *index >= state.workers.hired.len()) {
+        return false;
+    }
+
+    for (item, amount) in destination.get_supplies() {
+        state.inventory.add_item(item, -amount);
+    }
+
+    // back to front, so the remaining indices stay valid
+    let mut members = vec![];
+    for index in party.into_iter().rev() {
+        let mut worker = state.workers.hired.remove(index);
+        worker.assigned_slot = None;
+        members.push(worker);
+    }
+    members.reverse();
+
+    state.expeditions.active.push(Expedition {
+        destination,
+        party: members,
+        remaining_seconds: destination.get_duration(),
+    });
+
+    true
+}
+
+/// Hands out the loot and experience of a returned party and takes the workers back in.
+pub fn finish_expedition(state: &mut GameState, mut expedition: Expedition) {
+    let destination = expedition.destination;
+    let loot = destination.roll_loot(&mut state.rng);
+    let (skill, actions) = destination.get_experience();
+
+    for (item, amount) in &loot {
+        let room = state.village.storage_room(item, state.inventory.get_item_amount(item));
+        state.inventory.add_item(*item, (*amount).min(room));
+    }
+
+    let skill_instance = state.skill_archetype_instances.get_skill_by_type_mut(&skill);
+    for _ in 0..actions {
+        skill_instance.increment_actions();
+    }
+
+    for worker in &mut expedition.party {
+        for _ in 0..actions {
+            worker.experience.increment_actions();
+        }
+    }
+
+    state.workers.hired.append(&mut expedition.party);
+
+    let found = loot.iter()
+        .map(|(item, amount)| format!("{} {}", amount, item.get_name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    state.toasts.push(Toast {
+        text: format!("Back from {}: {}", destination.get_name(), if found.is_empty() { "nothing".to_string() } else { found }),
+        remaining_seconds: TOAST_SECONDS,
+    });
+}
+
+pub fn build_expeditions_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let destination_count = Destination::iter().count() as f32;
+    let column_width = (rect.w - padding * (destination_count + 1.0)) / destination_count;
+
+    elements.push(UiElement::Text {
+        content: format!("Expeditions - {} workers at home", state.workers.hired.len()),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    for (i, destination) in Destination::iter().enumerate() {
+        let x = rect.x + padding + i as f32 * (column_width + padding);
+        let mut y = rect.y + padding + 60.0;
+
+        elements.push(UiElement::Text {
+            content: destination.get_name().to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 20.0,
+            font_size: 20.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        y += 32.0;
+
+        let (skill, actions) = destination.get_experience();
+        let supplies = destination.get_supplies().iter()
+            .map(|(item, amount)| format!("{} {}", amount, item.get_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let details = [
+            format!("{} away, party of {}", pretty_duration(destination.get_duration()), destination.get_party_size()),
+            format!("Supplies: {}", supplies),
+            format!("{} actions of {} experience", actions, skill.get_name()),
+        ];
+
+        for line in details {
+            elements.push(UiElement::Text {
+                content: line,
+                font: assets.fonts.text.clone(),
+                x,
+                y: y + 14.0,
+                font_size: 14.0,
+                color: palette::BORDER.get_color(),
+            });
+
+            y += 22.0;
+        }
+
+        y += 8.0;
+
+        for entry in destination.get_loot_table() {
+            let amount = if entry.min == entry.max { entry.min.to_string() } else { format!("{}-{}", entry.min, entry.max) };
+
+            elements.push(UiElement::Text {
+                content: format!("{} {} ({:.0}%)", amount, entry.item.get_name(), entry.chance * 100.0),
+                font: assets.fonts.text.clone(),
+                x,
+                y: y + 14.0,
+                font_size: 14.0,
+                color: palette::TEXT.get_color(),
+            });
+
+            y += 20.0;
+        }
+
+        y += 16.0;
+
+        match state.expeditions.get_active(&destination) {
+            Some(expedition) => {
+                let party = expedition.party.iter()
+                    .map(|worker| worker.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                elements.push(UiElement::Text {
+                    content: format!("{} - back in {}", party, pretty_duration(expedition.remaining_seconds)),
+                    font: assets.fonts.text_bold.clone(),
+                    x,
+                    y: y + 14.0,
+                    font_size: 14.0,
+                    color: palette::TEXT.get_color(),
+                });
+
+                elements.push(UiElement::ProgressBar {
+                    x,
+                    y: y + 24.0,
+                    width: column_width,
+                    height: 12.0,
+                    progress: expedition.progress(),
+                    background_color: palette::BAR_BACKGROUND.get_color(),
+                    foreground_color: palette::PROGRESS_COLOR.get_color(),
+                    border_style: BorderStyle::Solid,
+                });
+            }
+            None => {
+                let party = pick_party(state, &destination);
+                let names = party.iter()
+                    .map(|index| state.workers.hired[*index].name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                elements.push(UiElement::RectButton {
+                    rectangle: UiRect::new(x, y, column_width, 32.0),
+                    font_size: 14.0,
+                    font: assets.fonts.text_bold.clone(),
+                    text: if names.is_empty() { "Send".to_string() } else { format!("Send {}", names) },
+                    background_color: if can_start_expedition(state, &destination) { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+                    text_color: palette::BUTTON_TEXT.get_color(),
+                    intent: Intent::StartExpedition(destination, party),
+                    parent_clip: None,
+                    border_style: BorderStyle::Solid,
+                });
+            }
+        }
+    }
+
+    elements
+}
@@ -1,9 +1,30 @@
 use crate::assets::AssetId::*;
 use crate::assets::Assets;
+use crate::achievements::{newly_completed, unlocked_toast, Achievements};
+use crate::automation::{Automation, Rule};
 use crate::draw::UiElement;
-use crate::job::{JobArchetype, JobArchetypeInstances, JobInstance};
-use crate::job_slot::{JobSlot, JobSlotState};
+use crate::buffs::{use_consumable, Buffs};
+use crate::combat::{start_fight, stop_fight, update_combat, Combat, Enemy};
+use crate::equipment::{Equipment, Tool};
+use crate::job::{JobArchetype, JobArchetypeInstances, JobInstance, JobModifiers, BASE_TIMESLOT_CAPACITY, FUEL_ITEM, UNFUELED_SPEED_MULTIPLIER};
+use crate::job_slot::{JobSlot, JobSlotState, QueuedJob, StopCondition};
+use crate::market::Market;
+use crate::optimizer::apply_plan;
+use crate::orders::{fulfill_order, generate_order, OrderBoard};
+use crate::prestige::{prestige_reset, Prestige, PrestigePerk};
+use crate::resource_nodes::{start_prospecting, vein_found_toast, ResourceNodes};
+use crate::research::{research_toast, start_research, Research, ResearchNode};
+use crate::rng::Rng;
+use crate::runes::{set_socket, socket_modifiers, Rune};
+use crate::shop::{buy_item, buy_upgrade, sell_item, Shop, Upgrade};
 use crate::skill::{SkillArchetype, SkillArchetypeInstances};
+use crate::spoilage::{aging_rate, Spoilage};
+use crate::village::{construct_building, Building, Village};
+use crate::expeditions::{finish_expedition, start_expedition, Destination, Expeditions};
+use crate::territory::{claim_tile, Territory};
+use crate::toasts::{Toast, Toasts, TOAST_SECONDS};
+use crate::villagers::{gift_item, Villager, Villagers};
+use crate::workers::{assign_worker, fire_worker, hire_worker, worker_left_toast, Workers};
 use macroquad::color::Color;
 use macroquad::input::MouseButton;
 use macroquad::math::Vec2;
@@ -11,8 +32,16 @@ use macroquad::miniquad::window::set_mouse_cursor;
 use macroquad::miniquad::CursorIcon;
 use macroquad::prelude::Texture2D;
 use std::collections::HashMap;
-use strum_macros::Display;
-use crate::job::{JobParameters, LumberingJobArchetype};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+use crate::job::LumberingJobArchetype;
+
+/// New games reseed the market and the game rng, tests keep these so they are reproducible.
+pub const DEFAULT_MARKET_SEED: u64 = 0x7469_6E79;
+pub const DEFAULT_SEED: u64 = 0x6669_656C_6473;
+
+/// Offline progress stops counting after this long, so a forgotten game doesn't run for weeks on load.
+pub const MAX_OFFLINE_SECONDS: f64 = 8.0 * 60.0 * 60.0;
 
 pub struct MouseInput {
     pub pressed: Vec<MouseButton>,
@@ -46,6 +75,26 @@ pub struct GameState {
     pub text_particles: Vec<TextParticle>,
     pub job_slots: Vec<JobSlot>,
     pub game_tab: GameTab,
+    pub show_rates_overlay: bool,
+    pub automation: Automation,
+    pub equipment: Equipment,
+    pub buffs: Buffs,
+    pub shop: Shop,
+    pub market: Market,
+    pub achievements: Achievements,
+    pub toasts: Toasts,
+    pub rng: Rng,
+    pub order_board: OrderBoard,
+    pub prestige: Prestige,
+    pub research: Research,
+    pub village: Village,
+    pub workers: Workers,
+    pub villagers: Villagers,
+    pub territory: Territory,
+    pub expeditions: Expeditions,
+    pub combat: Combat,
+    pub resource_nodes: ResourceNodes,
+    pub spoilage: Spoilage,
 }
 
 impl GameState {
@@ -56,8 +105,28 @@ impl GameState {
             game_meta: GameMeta::new(),
             inventory: Inventory::new(),
             text_particles: vec![],
-            job_slots: (0..6).map(|i| JobSlot { index: i, state: JobSlotState::Locked }).collect::<Vec<_>>(),
+            job_slots: (0..6).map(|i| JobSlot::new(i, JobSlotState::Locked)).collect::<Vec<_>>(),
             game_tab: GameTab::Jobs,
+            show_rates_overlay: false,
+            automation: Automation::new(),
+            equipment: Equipment::new(),
+            buffs: Buffs::new(),
+            shop: Shop::new(),
+            market: Market::new(DEFAULT_MARKET_SEED),
+            achievements: Achievements::new(),
+            toasts: Toasts::new(),
+            rng: Rng::new(DEFAULT_SEED),
+            order_board: OrderBoard::new(),
+            prestige: Prestige::new(),
+            research: Research::new(),
+            village: Village::new(),
+            workers: Workers::new(),
+            villagers: Villagers::new(),
+            territory: Territory::new(),
+            expeditions: Expeditions::new(),
+            combat: Combat::new(),
+            resource_nodes: ResourceNodes::new(),
+            spoilage: Spoilage::new(),
         }
     }
 
@@ -67,6 +136,119 @@ impl GameState {
             .collect::<Vec<_>>()
     }
 
+    /// Speed and yield modifiers for a job, based on equipped tools, buffs, fuel, shop upgrades,
+    /// achievement rewards, prestige perks, research, village buildings, friendly villagers and claimed land.
+    pub fn job_modifiers(&self, job_instance: &JobInstance) -> JobModifiers {
+        let tool_modifiers = self.equipment
+            .get_tool_for_skill(&job_instance.job_archetype.get_skill_type())
+            .map(|equipped| equipped.tool.get_modifiers())
+            .unwrap_or_else(JobModifiers::none);
+
+        let fuel_modifiers = if job_instance.job_archetype.uses_fuel() && self.inventory.get_item_amount(&FUEL_ITEM) < 1 {
+            JobModifiers { speed_multiplier: UNFUELED_SPEED_MULTIPLIER, ..JobModifiers::none() }
+        } else {
+            JobModifiers::none()
+        };
+
+        tool_modifiers
+            .combine(&self.buffs.get_modifiers(&job_instance.job_archetype))
+            .combine(&fuel_modifiers)
+            .combine(&self.shop.get_modifiers())
+            .combine(&self.achievements.get_modifiers(&job_instance.job_archetype))
+            .combine(&self.prestige.get_modifiers())
+            .combine(&self.research.get_modifiers())
+            .combine(&self.village.get_modifiers(&job_instance.job_archetype))
+            .combine(&self.villagers.get_modifiers(&job_instance.job_archetype))
+            .combine(&self.territory.get_modifiers(&job_instance.job_archetype))
+    }
+
+    /// Jobs need their research done and, for some, a claimed tile on the map.
+    pub fn is_job_available(&self, job_archetype: &JobArchetype) -> bool {
+        self.research.is_job_available(job_archetype) && self.territory.is_job_available(job_archetype)
+    }
+
+    pub fn timeslot_capacity(&self) -> i32 {
+        BASE_TIMESLOT_CAPACITY + self.shop.get_level(&Upgrade::LongerShifts) as i32
+    }
+
+    /// Time slots taken by running jobs, leaving out the given slot.
+    pub fn timeslots_used(&self, except_slot: Option<usize>) -> i32 {
+        self.job_slots.iter()
+            .filter(|slot| Some(slot.index) != except_slot)
+            .map(|slot| match &slot.state {
+                JobSlotState::RunningJob(job_instance) if job_instance.running => job_instance.timeslot_cost,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Whether the job could run in the slot, in place of whatever runs there now.
+    pub fn fits_timeslots(&self, slot_index: usize, job_archetype: &JobArchetype) -> bool {
+        self.timeslots_used(Some(slot_index)) + job_archetype.timeslot_cost() <= self.timeslot_capacity()
+    }
+
+    /// Modifiers for the job running in a slot, including its socketed runes and assigned worker.
+    pub fn slot_modifiers(&self, slot: &JobSlot) -> JobModifiers {
+        match &slot.state {
+            JobSlotState::RunningJob(job_instance) => self.job_modifiers(job_instance)
+                .combine(&socket_modifiers(&slot.sockets))
+                .combine(&self.workers.get_modifiers(slot.index, &job_instance.job_archetype)),
+            _ => JobModifiers::none(),
+        }
+    }
+
+    /// Whether the job in a slot moves on. Paused jobs don't, and neither do jobs that got in through a queue
+    /// while capacity was short, since the first slots get the time slots first. A full storage stalls a job
+    /// before it takes any inputs, and so does a grove that needs to grow back or a vein that has run dry.
+    pub fn is_slot_active(&self, slot: &JobSlot) -> bool {
+        let JobSlotState::RunningJob(job_instance) = &slot.state else {
+            return false;
+        };
+
+        let timeslots_used: i32 = self.job_slots.iter()
+            .take_while(|other| other.index != slot.index)
+            .map(|other| match &other.state {
+                JobSlotState::RunningJob(other_job) if other_job.running => other_job.timeslot_cost,
+                _ => 0,
+            })
+            .sum();
+
+        let product = job_instance.job_archetype.get_product();
+
+        job_instance.running
+            && timeslots_used + job_instance.timeslot_cost <= self.timeslot_capacity()
+            && self.village.storage_room(&product, self.inventory.get_item_amount(&product)) >= 1
+            && self.resource_nodes.has_resources(&job_instance.job_archetype)
+    }
+
+    /// Runs the game forward in steps of a second, the same way for time skips and offline progress.
+    pub fn catch_up(&mut self, seconds: f64) {
+        // skip capturing effects because we don't want to draw millions of events
+        for _ in 0..seconds.floor() as i64 {
+            self.update_progress(1.0);
+        }
+
+        if seconds.fract() > 0.0 {
+            self.update_progress(seconds.fract() as f32);
+        }
+    }
+
+    /// Catches up on the time the game was closed, up to [`MAX_OFFLINE_SECONDS`]. This runs the same one second
+    /// ticks as skipping time, a full absence is 28,800 of them.
+    pub fn catch_up_offline(&mut self, seconds: f64) {
+        let seconds = seconds.clamp(0.0, MAX_OFFLINE_SECONDS);
+
+        if seconds < 1.0 {
+            return;
+        }
+
+        self.catch_up(seconds);
+        self.toasts.push(Toast {
+            text: format!("Welcome back! {} passed while you were away", pretty_duration(seconds)),
+            remaining_seconds: TOAST_SECONDS,
+        });
+    }
+
     // Step logic (tick + inputs)
     pub fn step(&mut self, intents: &[Intent], dt: f32) -> Vec<EffectWithSource>
     {
@@ -83,24 +265,73 @@ impl GameState {
     {
         let mut effects_with_source = vec![];
 
-        let mut running_jobs: Vec<&mut JobInstance> = self.job_slots.iter_mut()
-            .filter_map(|slot| {
-                if let JobSlotState::RunningJob(job_instance) = &mut slot.state {
-                    Some(job_instance)
-                } else {
-                    None
-                }
-            })
+        self.buffs.update(dt);
+        self.market.update(dt);
+        self.toasts.update(dt);
+        if self.order_board.update(dt) {
+            let available_jobs: Vec<JobArchetype> = JobArchetype::all().into_iter().filter(|job| self.is_job_available(job)).collect();
+            let order = generate_order(&mut self.rng, &self.skill_archetype_instances, &available_jobs);
+            self.order_board.orders.push(order);
+        }
+
+        if let Some(node) = self.research.update(dt) {
+            self.toasts.push(research_toast(&node));
+        }
+
+        for worker in self.workers.update(&mut self.rng, &mut self.inventory, dt) {
+            self.toasts.push(worker_left_toast(&worker));
+        }
+
+        for expedition in self.expeditions.update(dt) {
+            finish_expedition(self, expedition);
+        }
+
+        update_combat(self, dt);
+
+        if let Some(node) = self.resource_nodes.update(&mut self.rng, dt) {
+            self.toasts.push(vein_found_toast(&node));
+        }
+
+        let aging_rate = aging_rate(self.village.get_level(&Building::Cellar));
+        for (item, amount) in self.spoilage.update(&mut self.inventory, aging_rate, dt) {
+            self.toasts.push(Toast {
+                text: format!("{} {} spoiled", amount, item.get_name()),
+                remaining_seconds: TOAST_SECONDS,
+            });
+        }
+
+        // automation rules act like the player would, before the jobs progress
+        for intent in self.automation.intents(self) {
+            intent.execute(self);
+        }
+
+        // modifiers depend on the whole game state, so collect them before borrowing the slots
+        let modifiers: Vec<JobModifiers> = self.job_slots.iter()
+            .map(|slot| self.slot_modifiers(slot))
             .collect();
 
-        for job_instance in &mut running_jobs {
-            if !job_instance.running {
+        for (index, modifiers) in modifiers.into_iter().enumerate() {
+            if !self.is_slot_active(&self.job_slots[index]) {
                 continue;
             }
 
-            let effects = job_instance.update_progress(&mut self.inventory, dt);
+            let slot = &mut self.job_slots[index];
+            let JobSlotState::RunningJob(job_instance) = &mut slot.state else {
+                continue;
+            };
+
+            let effects = job_instance.update_progress(&mut self.inventory, &modifiers, dt);
 
             for effect in effects {
+                // whatever doesn't fit into storage is lost
+                let effect = match effect {
+                    Effect::AddItem { item, amount } => Effect::AddItem {
+                        item,
+                        amount: amount.min(self.village.storage_room(&item, self.inventory.get_item_amount(&item))),
+                    },
+                    other => other,
+                };
+
                 // execute side effects
                 match &effect {
                     Effect::AddItem { item, amount } => {
@@ -108,9 +339,20 @@ impl GameState {
                     }
                     Effect::IncrementActionsForSkill { skill_type } => {
                         self.skill_archetype_instances.get_skill_by_type_mut(skill_type).increment_actions();
+                        self.equipment.wear(skill_type);
                     }
                     Effect::IncrementActionsForJobType { job_type } => {
                         self.job_archetype_instances.get_archetype_mut(job_type).increment_actions();
+                        self.resource_nodes.deplete(job_type);
+                        slot.queue.actions_completed += 1;
+
+                        if let Some(worker) = self.workers.get_assigned_mut(slot.index) {
+                            worker.experience.increment_actions();
+                        }
+
+                        if job_type.uses_fuel() && self.inventory.get_item_amount(&FUEL_ITEM) > 0 {
+                            self.inventory.add_item(FUEL_ITEM, -1);
+                        }
                     }
                 }
 
@@ -122,6 +364,18 @@ impl GameState {
             }
         }
 
+        for slot in &mut self.job_slots {
+            slot.advance_queue(&mut self.inventory);
+        }
+
+        self.achievements.record_effects(&effects_with_source);
+
+        for achievement in newly_completed(self) {
+            if self.achievements.unlock(achievement) {
+                self.toasts.push(unlocked_toast(&achievement));
+            }
+        }
+
         effects_with_source
     }
 }
@@ -133,6 +387,18 @@ pub enum GameTab {
     Inventory,
     Settings,
     Stats,
+    Automation,
+    Shop,
+    Achievements,
+    Orders,
+    Prestige,
+    Research,
+    Village,
+    Workers,
+    Villagers,
+    Territory,
+    Expeditions,
+    Combat,
 }
 
 #[derive(Clone)]
@@ -143,30 +409,94 @@ pub enum Intent {
     ChangeJobSlotState(usize, JobSlotState),
     SetMouseCursor(CursorIcon),
     SelectGameTab(GameTab),
+    ToggleRatesOverlay,
+    OptimizeSlots(Item),
+    SetStopCondition(usize, StopCondition),
+    QueueJob(usize, QueuedJob),
+    SetJobQueue(usize, Vec<QueuedJob>),
+    AddRule(Rule),
+    UpdateRule(usize, Rule),
+    RemoveRule(usize),
+    EquipTool(Tool),
+    UseItem(Item, Option<usize>),
+    UnlockJobSlot(usize),
+    BuyItem(Item, i64),
+    SellItem(Item, i64),
+    BuyUpgrade(Upgrade),
+    SelectMarketItem(Item),
+    FulfillOrder(usize),
+    DiscardOrder(usize),
+    Prestige,
+    BuyPrestigePerk(PrestigePerk),
+    StartResearch(ResearchNode),
+    PanResearch(f32, f32),
+    ConstructBuilding(Building),
+    SetSocket(usize, usize, Option<Rune>),
+    HireWorker(usize),
+    FireWorker(usize),
+    AssignWorker(usize, Option<usize>),
+    ClickJob(usize),
+    GiftItem(Villager, Item),
+    ClaimTile(usize, usize),
+    StartExpedition(Destination, Vec<usize>),
+    StartFight(Enemy),
+    StopFight,
+    ToggleCombatAbilities,
+    Prospect(JobArchetype),
 }
 
 impl Intent {
     pub fn execute(&self, game_state: &mut GameState) {
         match self {
             Intent::ToggleJob(index) => {
+                let fits = match game_state.job_slots.get(*index) {
+                    Some(JobSlot { state: JobSlotState::RunningJob(job_instance), .. }) => {
+                        job_instance.running || game_state.fits_timeslots(*index, &job_instance.job_archetype)
+                    }
+                    _ => false,
+                };
+
+                if !fits {
+                    return;
+                }
+
                 if let Some(JobSlot { state: JobSlotState::RunningJob(job_instance), .. }) = game_state.job_slots.get_mut(*index) {
                     job_instance.toggle_running();
                 }
             }
+            Intent::ClickJob(index) => {
+                if let Some(JobSlot { state: JobSlotState::RunningJob(job_instance), .. }) = game_state.job_slots.get_mut(*index) {
+                    job_instance.click(&mut game_state.rng);
+                }
+            }
             Intent::EnableHyperMode(index) => {
                 if let Some(JobSlot { state: JobSlotState::RunningJob(job_instance), .. }) = game_state.job_slots.get_mut(*index) {
+                    let was_enabled = job_instance.hyper_mode.is_enabled;
                     job_instance.hyper_mode.enable();
+
+                    if !was_enabled && job_instance.hyper_mode.is_enabled {
+                        game_state.achievements.hyper_activations += 1;
+                    }
                 }
             }
             Intent::SkipSeconds(seconds) => {
-                for _ in 0..*seconds {
-                    // skip capturing effects because we don't want to draw millions of events
-                    game_state.update_progress(1.0);
-                }
+                game_state.catch_up(*seconds as f64);
             }
             Intent::ChangeJobSlotState(index, new_state) => {
+                let available = match new_state {
+                    JobSlotState::PickingProduct(skill) => game_state.research.is_skill_available(skill),
+                    JobSlotState::RunningJob(job_instance) => game_state.is_job_available(&job_instance.job_archetype)
+                        && (!job_instance.running || game_state.fits_timeslots(*index, &job_instance.job_archetype)),
+                    _ => true,
+                };
+
+                if !available {
+                    return;
+                }
+
                 if let Some(slot) = game_state.job_slots.get_mut(*index) {
-                    slot.state = new_state.clone();
+                    slot.refund_paid_inputs(&mut game_state.inventory);
+                    slot.set_state(new_state.clone());
                 }
             },
             Intent::SetMouseCursor(cursor_icon) => {
@@ -175,6 +505,143 @@ impl Intent {
             Intent::SelectGameTab(tab) => {
                 game_state.game_tab = tab.clone();
             }
+            Intent::ToggleRatesOverlay => {
+                game_state.show_rates_overlay = !game_state.show_rates_overlay;
+            }
+            Intent::OptimizeSlots(target) => {
+                apply_plan(game_state, *target);
+            }
+            Intent::SetStopCondition(index, stop_condition) => {
+                if let Some(slot) = game_state.job_slots.get_mut(*index) {
+                    slot.queue.stop_condition = stop_condition.clone();
+                }
+            }
+            Intent::QueueJob(index, queued_job) => {
+                if !game_state.is_job_available(&queued_job.job_archetype) {
+                    return;
+                }
+
+                if let Some(slot) = game_state.job_slots.get_mut(*index) {
+                    slot.queue.upcoming.push_back(queued_job.clone());
+                }
+            }
+            Intent::SetJobQueue(index, queued_jobs) => {
+                if !queued_jobs.iter().all(|queued_job| game_state.is_job_available(&queued_job.job_archetype)) {
+                    return;
+                }
+
+                if let Some(slot) = game_state.job_slots.get_mut(*index) {
+                    slot.queue.upcoming = queued_jobs.iter().cloned().collect();
+                }
+            }
+            Intent::AddRule(rule) => {
+                if game_state.automation.rules.len() < game_state.research.rule_limit() {
+                    game_state.automation.rules.push(rule.clone());
+                }
+            }
+            Intent::UpdateRule(index, rule) => {
+                if let Some(existing) = game_state.automation.rules.get_mut(*index) {
+                    *existing = rule.clone();
+                }
+            }
+            Intent::RemoveRule(index) => {
+                if *index < game_state.automation.rules.len() {
+                    game_state.automation.rules.remove(*index);
+                }
+            }
+            Intent::EquipTool(tool) => {
+                game_state.equipment.equip(*tool, &mut game_state.inventory);
+            }
+            Intent::UseItem(item, slot_index) => {
+                use_consumable(game_state, *item, *slot_index);
+            }
+            Intent::UnlockJobSlot(index) => {
+                let Some(slot) = game_state.job_slots.get_mut(*index) else { return };
+
+                if !matches!(slot.state, JobSlotState::Locked) || slot.index >= game_state.research.slot_limit() {
+                    return;
+                }
+
+                let cost = slot.unlock_cost();
+
+                if cost.iter().any(|(item, amount)| game_state.inventory.get_item_amount(item) < *amount) {
+                    return;
+                }
+
+                for (item, amount) in cost {
+                    game_state.inventory.add_item(item, -amount);
+                }
+
+                slot.set_state(JobSlotState::PickingCategory);
+            }
+            Intent::BuyItem(item, amount) => {
+                buy_item(game_state, *item, *amount);
+            }
+            Intent::SellItem(item, amount) => {
+                sell_item(game_state, *item, *amount);
+            }
+            Intent::BuyUpgrade(upgrade) => {
+                buy_upgrade(game_state, *upgrade);
+            }
+            Intent::SelectMarketItem(item) => {
+                game_state.market.chart_item = Some(*item);
+            }
+            Intent::FulfillOrder(index) => {
+                fulfill_order(game_state, *index);
+            }
+            Intent::DiscardOrder(index) => {
+                if *index < game_state.order_board.orders.len() {
+                    game_state.order_board.orders.remove(*index);
+                }
+            }
+            Intent::Prestige => {
+                prestige_reset(game_state);
+            }
+            Intent::BuyPrestigePerk(perk) => {
+                game_state.prestige.buy_perk(*perk);
+            }
+            Intent::StartResearch(node) => {
+                start_research(game_state, *node);
+            }
+            Intent::PanResearch(dx, dy) => {
+                game_state.research.pan += Vec2::new(*dx, *dy);
+            }
+            Intent::ConstructBuilding(building) => {
+                construct_building(game_state, *building);
+            }
+            Intent::SetSocket(slot_index, socket_index, rune) => {
+                set_socket(game_state, *slot_index, *socket_index, *rune);
+            }
+            Intent::HireWorker(index) => {
+                hire_worker(game_state, *index);
+            }
+            Intent::FireWorker(index) => {
+                fire_worker(game_state, *index);
+            }
+            Intent::AssignWorker(index, slot_index) => {
+                assign_worker(game_state, *index, *slot_index);
+            }
+            Intent::GiftItem(villager, item) => {
+                gift_item(game_state, *villager, *item);
+            }
+            Intent::ClaimTile(col, row) => {
+                claim_tile(game_state, *col, *row);
+            }
+            Intent::StartExpedition(destination, party) => {
+                start_expedition(game_state, *destination, party);
+            }
+            Intent::StartFight(enemy) => {
+                start_fight(game_state, *enemy);
+            }
+            Intent::StopFight => {
+                stop_fight(game_state);
+            }
+            Intent::ToggleCombatAbilities => {
+                game_state.combat.use_abilities = !game_state.combat.use_abilities;
+            }
+            Intent::Prospect(job_archetype) => {
+                start_prospecting(game_state, *job_archetype);
+            }
         }
     }
 }
@@ -251,10 +718,22 @@ pub fn pretty_number(num: i64) -> String {
     format!("{:.2}{suffix}", num)
 }
 
-#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+pub fn pretty_duration(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as i64;
+
+    match seconds {
+        s if s >= 86_400 => format!("{}d {}h", s / 86_400, (s % 86_400) / 3_600),
+        s if s >= 3_600 => format!("{}h {}m", s / 3_600, (s % 3_600) / 60),
+        s if s >= 60 => format!("{}m {}s", s / 60, s % 60),
+        s => format!("{}s", s),
+    }
+}
+
+#[derive(EnumIter, Hash, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum WoodItem {
     Craftwood,
     Graintree,
+    Kindlewood,
 }
 
 trait GetName {
@@ -266,13 +745,26 @@ impl GetName for WoodItem {
         match self {
             WoodItem::Craftwood => "Craftwood".to_string(),
             WoodItem::Graintree => "Graintree".to_string(),
+            WoodItem::Kindlewood => "Kindlewood".to_string(),
         }
     }
 }
 
-#[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
+#[derive(EnumIter, Hash, Eq, PartialEq, Clone, Copy, Debug)]
 pub enum WoodWorkingItem {
     Plank,
+    Beam,
+    Crate,
+}
+
+impl GetName for WoodWorkingItem {
+    fn get_name(&self) -> String {
+        match self {
+            WoodWorkingItem::Plank => "Plank".to_string(),
+            WoodWorkingItem::Beam => "Beam".to_string(),
+            WoodWorkingItem::Crate => "Crate".to_string(),
+        }
+    }
 }
 
 #[derive(Hash, Eq, PartialEq, Clone, Copy, Debug)]
@@ -287,14 +779,36 @@ pub enum Item {
     IronBar,
     Sandwich,
     ManaPotion,
+    Tool(Tool),
+    Rune(Rune),
 }
 
 impl Item {
+    pub fn all() -> Vec<Item> {
+        let simple_items = [
+            Item::Coin,
+            Item::IronOre,
+            Item::Herb,
+            Item::Meat,
+            Item::Berry,
+            Item::IronBar,
+            Item::Sandwich,
+            Item::ManaPotion,
+        ];
+
+        WoodItem::iter().map(Item::Wood)
+            .chain(WoodWorkingItem::iter().map(Item::Woodworking))
+            .chain(simple_items)
+            .chain(Tool::iter().map(Item::Tool))
+            .chain(Rune::iter().map(Item::Rune))
+            .collect()
+    }
+
     pub fn get_name(&self) -> String {
         match self {
             Item::Coin => "Coin".to_string(),
             Item::Wood(item) => item.get_name(),
-            Item::Woodworking(WoodWorkingItem::Plank) => "Plank".to_string(),
+            Item::Woodworking(item) => item.get_name(),
             Item::IronOre => "Iron".to_string(),
             Item::Herb => "Herb".to_string(),
             Item::Meat => "Meat".to_string(),
@@ -302,6 +816,8 @@ impl Item {
             Item::IronBar => "Iron Bar".to_string(),
             Item::Sandwich => "Sandwich".to_string(),
             Item::ManaPotion => "Mana Potion".to_string(),
+            Item::Tool(tool) => tool.get_name().to_string(),
+            Item::Rune(rune) => rune.get_name().to_string(),
         }
     }
 
@@ -309,12 +825,18 @@ impl Item {
         match self {
             Item::Wood(WoodItem::Craftwood) => Craftwood.get_texture(assets),
             Item::Wood(WoodItem::Graintree) => Graintree.get_texture(assets),
+            Item::Wood(WoodItem::Kindlewood) => Kindlewood.get_texture(assets),
+            Item::Woodworking(WoodWorkingItem::Plank) => Plank.get_texture(assets),
+            Item::Woodworking(WoodWorkingItem::Beam) => Beam.get_texture(assets),
+            Item::Woodworking(WoodWorkingItem::Crate) => Crate.get_texture(assets),
             Item::Meat => MeatGame.get_texture(assets),
             Item::Coin => Coin.get_texture(assets),
             Item::Herb => Herbs.get_texture(assets),
             Item::IronOre => IronOre.get_texture(assets),
             Item::Sandwich => Sandwich.get_texture(assets),
             Item::ManaPotion => ManaPotion.get_texture(assets),
+            Item::Rune(rune) => rune.get_texture(assets),
+            Item::Tool(tool) => tool.get_texture(assets),
             _default => Texture2D::empty(),
         }
     }
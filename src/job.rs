@@ -1,13 +1,38 @@
 use crate::counts_actions::CountsActions;
-use crate::game::{Effect, Inventory, Item, Progress, WoodItem};
+use crate::equipment::Tool;
+use crate::game::{Effect, Inventory, Item, Progress, WoodItem, WoodWorkingItem};
+use crate::rng::Rng;
+use crate::runes::Rune;
 use crate::skill::SkillArchetype;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
+/// Burned by smithing and cooking jobs, one per action.
+pub const FUEL_ITEM: Item = Item::Wood(WoodItem::Kindlewood);
+pub const UNFUELED_SPEED_MULTIPLIER: f64 = 0.5;
+
+/// Time slots the player can fill with running jobs before any upgrade.
+pub const BASE_TIMESLOT_CAPACITY: i32 = 5;
+
+/// Seconds of progress the first click adds, later clicks add less until the hand rests.
+pub const CLICK_PROGRESS_SECONDS: f64 = 0.5;
+pub const CLICK_CRIT_CHANCE: f64 = 0.1;
+pub const CLICK_CRIT_MULTIPLIER: f64 = 3.0;
+
+/// Clicks closer together than this are ignored, so an auto-clicker is no better than a quick hand.
+pub const MIN_CLICK_INTERVAL: f64 = 0.1;
+
+/// Clicks that charge hyper mode as much as one finished action.
+pub const CLICKS_PER_HYPER_ACTION: i32 = 10;
+
+/// Share of the click fatigue that wears off per second.
+pub const CLICK_FATIGUE_RECOVERY: f64 = 0.5;
+
 #[derive(EnumIter, Clone, PartialEq, Eq, Hash, Debug, Copy)]
 pub enum LumberingJobArchetype {
     Craftwood,
     Graintree,
+    Kindlewood,
 }
 
 #[derive(EnumIter, Clone, PartialEq, Eq, Hash, Debug, Copy)]
@@ -33,11 +58,28 @@ pub enum CookingJobArchetype {
 #[derive(EnumIter, Clone, PartialEq, Eq, Hash, Debug, Copy)]
 pub enum AlchemyJobArchetype {
     ManaPotion,
+    BlankRune,
+    TimeRune,
+    HeartRune,
+    GreaterTimeRune,
+    GreaterHeartRune,
 }
 
 #[derive(EnumIter, Clone, PartialEq, Eq, Hash, Debug, Copy)]
 pub enum SmithingJobArchetype {
     IronBar,
+    Axe,
+    Pickaxe,
+    Knife,
+    Mortar,
+}
+
+#[derive(EnumIter, Clone, PartialEq, Eq, Hash, Debug, Copy)]
+pub enum WoodworkingJobArchetype {
+    Plank,
+    GrainPlank,
+    Beam,
+    Crate,
 }
 
 #[derive(Clone, PartialEq, Eq, Hash, Debug, Copy)]
@@ -49,9 +91,22 @@ pub enum JobArchetype {
     Smithing(SmithingJobArchetype),
     Cooking(CookingJobArchetype),
     Alchemy(AlchemyJobArchetype),
+    Woodworking(WoodworkingJobArchetype),
 }
 
 impl JobArchetype {
+    pub fn all() -> Vec<JobArchetype> {
+        LumberingJobArchetype::iter().map(JobArchetype::Lumbering)
+            .chain(MiningJobArchetype::iter().map(JobArchetype::Mining))
+            .chain(HuntingJobArchetype::iter().map(JobArchetype::Hunting))
+            .chain(ForagingJobArchetype::iter().map(JobArchetype::Foraging))
+            .chain(CookingJobArchetype::iter().map(JobArchetype::Cooking))
+            .chain(AlchemyJobArchetype::iter().map(JobArchetype::Alchemy))
+            .chain(SmithingJobArchetype::iter().map(JobArchetype::Smithing))
+            .chain(WoodworkingJobArchetype::iter().map(JobArchetype::Woodworking))
+            .collect()
+    }
+
     pub fn base_duration(&self) -> f64 {
         match self {
             _ => 4.0,
@@ -62,12 +117,26 @@ impl JobArchetype {
         match self {
             JobArchetype::Lumbering(LumberingJobArchetype::Craftwood) => "Craftwood".to_string(),
             JobArchetype::Lumbering(LumberingJobArchetype::Graintree) => "Graintree".to_string(),
+            JobArchetype::Lumbering(LumberingJobArchetype::Kindlewood) => "Kindlewood".to_string(),
             JobArchetype::Mining(MiningJobArchetype::Iron) => "Mining".to_string(),
             JobArchetype::Hunting(HuntingJobArchetype::Deer) => "Deer".to_string(),
             JobArchetype::Cooking(CookingJobArchetype::Sandwich) => "Sandwich".to_string(),
             JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion) => "Mana Potion".to_string(),
+            JobArchetype::Alchemy(AlchemyJobArchetype::BlankRune) => "Blank Rune".to_string(),
+            JobArchetype::Alchemy(AlchemyJobArchetype::TimeRune) => "Time Rune".to_string(),
+            JobArchetype::Alchemy(AlchemyJobArchetype::HeartRune) => "Heart Rune".to_string(),
+            JobArchetype::Alchemy(AlchemyJobArchetype::GreaterTimeRune) => "Greater Time Rune".to_string(),
+            JobArchetype::Alchemy(AlchemyJobArchetype::GreaterHeartRune) => "Greater Heart Rune".to_string(),
             JobArchetype::Foraging(ForagingJobArchetype::Herb) => "Herb".to_string(),
             JobArchetype::Smithing(SmithingJobArchetype::IronBar) => "Iron Bar".to_string(),
+            JobArchetype::Smithing(SmithingJobArchetype::Axe) => "Axe".to_string(),
+            JobArchetype::Smithing(SmithingJobArchetype::Pickaxe) => "Pickaxe".to_string(),
+            JobArchetype::Smithing(SmithingJobArchetype::Knife) => "Knife".to_string(),
+            JobArchetype::Smithing(SmithingJobArchetype::Mortar) => "Mortar".to_string(),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Plank) => "Plank".to_string(),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::GrainPlank) => "Grain Plank".to_string(),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Beam) => "Beam".to_string(),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Crate) => "Crate".to_string(),
         }
     }
 
@@ -75,12 +144,26 @@ impl JobArchetype {
         match self {
             JobArchetype::Lumbering(LumberingJobArchetype::Craftwood) => Item::Wood(WoodItem::Craftwood),
             JobArchetype::Lumbering(LumberingJobArchetype::Graintree) => Item::Wood(WoodItem::Graintree),
+            JobArchetype::Lumbering(LumberingJobArchetype::Kindlewood) => Item::Wood(WoodItem::Kindlewood),
             JobArchetype::Mining(MiningJobArchetype::Iron) => Item::IronOre,
             JobArchetype::Hunting(HuntingJobArchetype::Deer) => Item::Meat,
             JobArchetype::Cooking(CookingJobArchetype::Sandwich) => Item::Sandwich,
             JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion) => Item::ManaPotion,
+            JobArchetype::Alchemy(AlchemyJobArchetype::BlankRune) => Item::Rune(Rune::Blank),
+            JobArchetype::Alchemy(AlchemyJobArchetype::TimeRune) => Item::Rune(Rune::Time),
+            JobArchetype::Alchemy(AlchemyJobArchetype::HeartRune) => Item::Rune(Rune::Heart),
+            JobArchetype::Alchemy(AlchemyJobArchetype::GreaterTimeRune) => Item::Rune(Rune::GreaterTime),
+            JobArchetype::Alchemy(AlchemyJobArchetype::GreaterHeartRune) => Item::Rune(Rune::GreaterHeart),
             JobArchetype::Foraging(ForagingJobArchetype::Herb) => Item::Herb,
             JobArchetype::Smithing(SmithingJobArchetype::IronBar) => Item::IronBar,
+            JobArchetype::Smithing(SmithingJobArchetype::Axe) => Item::Tool(Tool::Axe),
+            JobArchetype::Smithing(SmithingJobArchetype::Pickaxe) => Item::Tool(Tool::Pickaxe),
+            JobArchetype::Smithing(SmithingJobArchetype::Knife) => Item::Tool(Tool::Knife),
+            JobArchetype::Smithing(SmithingJobArchetype::Mortar) => Item::Tool(Tool::Mortar),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Plank) => Item::Woodworking(WoodWorkingItem::Plank),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::GrainPlank) => Item::Woodworking(WoodWorkingItem::Plank),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Beam) => Item::Woodworking(WoodWorkingItem::Beam),
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Crate) => Item::Woodworking(WoodWorkingItem::Crate),
         }
     }
 
@@ -94,23 +177,90 @@ impl JobArchetype {
             JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion) => vec![
                 (Item::Herb, 2),
             ],
+            JobArchetype::Alchemy(AlchemyJobArchetype::BlankRune) => vec![
+                (Item::IronBar, 1),
+                (Item::Herb, 2),
+            ],
+            JobArchetype::Alchemy(AlchemyJobArchetype::TimeRune) => vec![
+                (Item::Rune(Rune::Blank), 1),
+                (Item::ManaPotion, 2),
+            ],
+            JobArchetype::Alchemy(AlchemyJobArchetype::HeartRune) => vec![
+                (Item::Rune(Rune::Blank), 1),
+                (Item::Meat, 3),
+            ],
+            JobArchetype::Alchemy(AlchemyJobArchetype::GreaterTimeRune) => vec![
+                (Item::Rune(Rune::Time), 3),
+                (Item::ManaPotion, 5),
+            ],
+            JobArchetype::Alchemy(AlchemyJobArchetype::GreaterHeartRune) => vec![
+                (Item::Rune(Rune::Heart), 3),
+                (Item::ManaPotion, 5),
+            ],
             JobArchetype::Smithing(SmithingJobArchetype::IronBar) => vec![
                 (Item::IronOre, 2),
             ],
+            JobArchetype::Smithing(SmithingJobArchetype::Axe) => vec![
+                (Item::IronBar, 3),
+                (Item::Wood(WoodItem::Craftwood), 2),
+            ],
+            JobArchetype::Smithing(SmithingJobArchetype::Pickaxe) => vec![
+                (Item::IronBar, 3),
+                (Item::Wood(WoodItem::Craftwood), 2),
+            ],
+            JobArchetype::Smithing(SmithingJobArchetype::Knife) => vec![
+                (Item::IronBar, 2),
+                (Item::Wood(WoodItem::Craftwood), 1),
+            ],
+            JobArchetype::Smithing(SmithingJobArchetype::Mortar) => vec![
+                (Item::IronBar, 4),
+            ],
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Plank) => vec![
+                (Item::Wood(WoodItem::Craftwood), 2),
+            ],
+            JobArchetype::Woodworking(WoodworkingJobArchetype::GrainPlank) => vec![
+                (Item::Wood(WoodItem::Graintree), 1),
+            ],
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Beam) => vec![
+                (Item::Woodworking(WoodWorkingItem::Plank), 3),
+            ],
+            JobArchetype::Woodworking(WoodworkingJobArchetype::Crate) => vec![
+                (Item::Woodworking(WoodWorkingItem::Plank), 4),
+                (Item::IronBar, 1),
+            ],
             _ => vec![],
         }
     }
 
+    /// Fuel isn't a required item: these jobs still run without it, only slower.
+    pub fn uses_fuel(&self) -> bool {
+        matches!(self.get_skill_type(), SkillArchetype::Smithing | SkillArchetype::Cooking)
+    }
+
+    /// Time slots the job takes while it runs. Smithing and alchemy keep more hands busy.
+    pub fn timeslot_cost(&self) -> i32 {
+        match self.get_skill_type() {
+            SkillArchetype::Smithing | SkillArchetype::Alchemy => 2,
+            _ => 1,
+        }
+    }
+
     pub fn get_skill_type(&self) -> SkillArchetype {
         match self {
             JobArchetype::Lumbering(LumberingJobArchetype::Craftwood) => SkillArchetype::Lumbering,
             JobArchetype::Lumbering(LumberingJobArchetype::Graintree) => SkillArchetype::Lumbering,
+            JobArchetype::Lumbering(LumberingJobArchetype::Kindlewood) => SkillArchetype::Lumbering,
             JobArchetype::Mining(MiningJobArchetype::Iron) => SkillArchetype::Mining,
             JobArchetype::Hunting(HuntingJobArchetype::Deer) => SkillArchetype::Hunting,
             JobArchetype::Foraging(ForagingJobArchetype::Herb) => SkillArchetype::Foraging,
             JobArchetype::Cooking(CookingJobArchetype::Sandwich) => SkillArchetype::Cooking,
-            JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion) => SkillArchetype::Alchemy,
+            JobArchetype::Alchemy(_) => SkillArchetype::Alchemy,
             JobArchetype::Smithing(SmithingJobArchetype::IronBar) => SkillArchetype::Smithing,
+            JobArchetype::Smithing(SmithingJobArchetype::Axe) => SkillArchetype::Smithing,
+            JobArchetype::Smithing(SmithingJobArchetype::Pickaxe) => SkillArchetype::Smithing,
+            JobArchetype::Smithing(SmithingJobArchetype::Knife) => SkillArchetype::Smithing,
+            JobArchetype::Smithing(SmithingJobArchetype::Mortar) => SkillArchetype::Smithing,
+            JobArchetype::Woodworking(_) => SkillArchetype::Woodworking,
         }
     }
 }
@@ -143,6 +293,34 @@ impl JobArchetypeInstance {
     }
 }
 
+/// Multipliers applied on top of a job's base duration and yield.
+/// Computed by the game state for every running job each tick.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct JobModifiers {
+    pub speed_multiplier: f64,
+    pub yield_multiplier: f64,
+    pub hyper_multiplier: f64,
+}
+
+impl JobModifiers {
+    pub fn none() -> Self {
+        Self {
+            speed_multiplier: 1.0,
+            yield_multiplier: 1.0,
+            hyper_multiplier: 1.0,
+        }
+    }
+
+    /// Stacks two sets of modifiers multiplicatively.
+    pub fn combine(&self, other: &JobModifiers) -> JobModifiers {
+        JobModifiers {
+            speed_multiplier: self.speed_multiplier * other.speed_multiplier,
+            yield_multiplier: self.yield_multiplier * other.yield_multiplier,
+            hyper_multiplier: self.hyper_multiplier * other.hyper_multiplier,
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct JobInstance {
     pub job_archetype: JobArchetype,
@@ -152,6 +330,8 @@ pub struct JobInstance {
     pub timeslot_cost: i32,
     pub has_paid_resources: bool,
     pub hyper_mode: HyperMode,
+    pub yield_accumulator: f64,
+    pub clicks: Clicks,
 }
 
 pub struct JobParameters {
@@ -211,6 +391,34 @@ impl HyperMode {
     }
 }
 
+/// Clicks on a running job. Each click tires the hand a little more, so spamming gives less and less.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Clicks {
+    pub fatigue: f64,
+    pub cooldown_seconds: f64,
+    pub hyper_charge: i32,
+}
+
+impl Clicks {
+    pub fn new() -> Self {
+        Self {
+            fatigue: 0.0,
+            cooldown_seconds: 0.0,
+            hyper_charge: 0,
+        }
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.fatigue *= (-CLICK_FATIGUE_RECOVERY * dt as f64).exp();
+        self.cooldown_seconds = (self.cooldown_seconds - dt as f64).max(0.0);
+    }
+
+    /// Seconds of progress for the next click, before a critical hit.
+    pub fn next_progress(&self) -> f64 {
+        CLICK_PROGRESS_SECONDS / (1.0 + self.fatigue)
+    }
+}
+
 impl JobInstance {
     pub fn new(p: JobParameters) -> Self {
         Self {
@@ -218,62 +426,129 @@ impl JobInstance {
             running: true, // todo: change to false
             action_progress: Progress{value: 0.0},
             time_accumulator: 0.0,
-            timeslot_cost: 1,
+            timeslot_cost: p.job_archetype.timeslot_cost(),
             has_paid_resources: false,
             hyper_mode: HyperMode::new(),
+            yield_accumulator: 0.0,
+            clicks: Clicks::new(),
+        }
+    }
+
+    /// Pushes the current action forward by hand. Returns the seconds of progress added,
+    /// or None if the click came too soon or the job isn't working right now.
+    pub fn click(&mut self, rng: &mut Rng) -> Option<f64> {
+        if !self.running || !self.has_paid_resources || self.clicks.cooldown_seconds > 0.0 {
+            return None;
+        }
+
+        let mut progress = self.clicks.next_progress();
+        if rng.chance(CLICK_CRIT_CHANCE) {
+            progress *= CLICK_CRIT_MULTIPLIER;
+        }
+
+        // the action itself finishes in the next update, clicks can't bank progress beyond it
+        let duration = self.job_archetype.base_duration();
+        self.time_accumulator = (self.time_accumulator + progress).min(duration);
+        self.action_progress.set(self.time_accumulator / duration);
+
+        self.clicks.fatigue += 1.0;
+        self.clicks.cooldown_seconds = MIN_CLICK_INTERVAL;
+        self.clicks.hyper_charge += 1;
+
+        if self.clicks.hyper_charge >= CLICKS_PER_HYPER_ACTION {
+            self.clicks.hyper_charge = 0;
+            self.hyper_mode.increment_actions();
         }
+
+        Some(progress)
     }
 
     pub fn toggle_running(&mut self) -> () {
         self.running = !self.running;
     }
 
-    pub fn update_progress(&mut self, inventory: &mut Inventory, dt: f32) -> Vec<Effect> {
-        let duration = self.job_archetype.base_duration();
+    /// Progress speed relative to the base duration, including hyper mode.
+    pub fn speed_multiplier(&self, modifiers: &JobModifiers) -> f64 {
+        if self.hyper_mode.is_enabled {
+            modifiers.speed_multiplier * self.hyper_mode.hyper_multiplier * modifiers.hyper_multiplier
+        } else {
+            modifiers.speed_multiplier
+        }
+    }
 
-        if !self.has_paid_resources {
-            // Check if we have the required items to start the job
-            let required_items = self.job_archetype.get_required_items();
+    pub fn actions_per_hour(&self, modifiers: &JobModifiers) -> f64 {
+        3600.0 * self.speed_multiplier(modifiers) / self.job_archetype.base_duration()
+    }
 
-            for (item, amount) in &required_items {
-                if inventory.get_item_amount(&item) < *amount {
-                    // Not enough resources to start the job
-                    return vec![];
-                }
-            }
+    /// Whether the next action is paid for or its inputs are in the inventory.
+    pub fn can_start(&self, inventory: &Inventory) -> bool {
+        self.has_paid_resources || self.job_archetype.get_required_items().iter()
+            .all(|(item, amount)| inventory.get_item_amount(item) >= *amount)
+    }
 
-            // Deduct the required items from the inventory
-            for (item, amount) in required_items {
-                inventory.add_item(item, -amount);
-            }
+    /// Takes the inputs of the next action out of the inventory, unless they are already paid.
+    /// Returns false while they aren't there.
+    fn pay_resources(&mut self, inventory: &mut Inventory) -> bool {
+        if self.has_paid_resources {
+            return true;
+        }
+
+        if !self.can_start(inventory) {
+            // Not enough resources to start the job
+            return false;
+        }
+
+        // Deduct the required items from the inventory
+        for (item, amount) in self.job_archetype.get_required_items() {
+            inventory.add_item(item, -amount);
+        }
+
+        self.has_paid_resources = true;
+        true
+    }
+
+    pub fn update_progress(&mut self, inventory: &mut Inventory, modifiers: &JobModifiers, dt: f32) -> Vec<Effect> {
+        let duration = self.job_archetype.base_duration();
+
+        self.clicks.update(dt);
 
-            self.has_paid_resources = true;
+        if !self.pay_resources(inventory) {
+            return vec![];
         }
 
         self.hyper_mode.update(dt);
 
-        let dt_progress = if self.hyper_mode.is_enabled {
-            dt * self.hyper_mode.hyper_multiplier as f32
-        } else {
-            dt
-        };
+        let dt_progress = dt as f64 * self.speed_multiplier(modifiers);
 
-        self.time_accumulator += dt_progress as f64;
-        self.action_progress.set(self.time_accumulator / duration);
+        self.time_accumulator += dt_progress;
+
+        let mut effects = vec![];
 
-        if self.time_accumulator >= duration {
+        // long steps from time skips finish as many actions as they have time for, each paying its own inputs
+        while self.time_accumulator >= duration {
             self.time_accumulator -= duration;
             self.has_paid_resources = false;
             self.hyper_mode.increment_actions();
 
-            vec![
-                Effect::AddItem { item: self.job_archetype.get_product(), amount: 1 },
+            // fractional yields carry over to the next action
+            self.yield_accumulator += modifiers.yield_multiplier;
+            let amount = self.yield_accumulator.floor() as i64;
+            self.yield_accumulator -= amount as f64;
+
+            effects.extend([
+                Effect::AddItem { item: self.job_archetype.get_product(), amount },
                 Effect::IncrementActionsForSkill { skill_type: self.job_archetype.get_skill_type() },
                 Effect::IncrementActionsForJobType { job_type: self.job_archetype.clone() },
-            ]
-        } else {
-            vec![]
+            ]);
+
+            if !self.pay_resources(inventory) {
+                break;
+            }
         }
+
+        self.action_progress.set(self.time_accumulator / duration);
+
+        effects
     }
 }
 
@@ -283,13 +558,7 @@ pub struct JobArchetypeInstances {
 
 impl JobArchetypeInstances {
     pub fn new() -> Self {
-        let instances = LumberingJobArchetype::iter().map(JobArchetype::Lumbering)
-            .chain(MiningJobArchetype::iter().map(JobArchetype::Mining))
-            .chain(HuntingJobArchetype::iter().map(JobArchetype::Hunting))
-            .chain(ForagingJobArchetype::iter().map(JobArchetype::Foraging))
-            .chain(CookingJobArchetype::iter().map(JobArchetype::Cooking))
-            .chain(AlchemyJobArchetype::iter().map(JobArchetype::Alchemy))
-            .chain(SmithingJobArchetype::iter().map(JobArchetype::Smithing))
+        let instances = JobArchetype::all().into_iter()
             .map(|archetype| JobArchetypeInstance::new(archetype))
             .collect();
 
@@ -1,12 +1,17 @@
-use crate::assets::AssetId::{BackgroundParchment, LockIcon, ParchmentFrame};
+use crate::assets::AssetId::{BackgroundParchment, LockIcon, ParchmentFrame, WoodBurner};
 use crate::assets::{AssetId, Assets};
 use crate::draw::{number_pill, BorderStyle, UiElement};
-use crate::game::{GameState, Intent, UiRect};
-use crate::job::{JobInstance, JobParameters};
+use crate::equipment::{Tool, TOOL_DURABILITY};
+use crate::game::{pretty_duration, GameState, Intent, Inventory, Item, UiRect, WoodWorkingItem};
+use crate::job::{JobArchetype, JobInstance, JobParameters, FUEL_ITEM};
 use crate::palette;
 use crate::palette::PaletteC;
+use crate::resource_nodes::{can_prospect, NodeKind, PROSPECT_COST};
+use crate::runes::{describe_modifiers, socket_modifiers, Rune, SOCKETS_PER_SLOT};
 use crate::skill::{SkillArchetype, SkillCategory};
+use macroquad::color::Color;
 use macroquad::prelude::Vec2;
+use std::collections::VecDeque;
 use strum::IntoEnumIterator;
 
 pub const WINDOW_PADDING: f32 = 16.0;
@@ -48,12 +53,18 @@ impl JobSlotState {
         let layout = CardLayout::new(16.0, 16.0, 5.0, 5.0);
 
         let state_specific_elements = match self {
-            JobSlotState::Locked => locked_job_slot_ui(job_slot_index, assets, offset),
-            JobSlotState::Empty => empty_job_slot_ui(job_slot_index, assets, offset),
+            JobSlotState::Locked => locked_job_slot_ui(&state.job_slots[job_slot_index], state, assets, offset),
+            JobSlotState::Empty => empty_job_slot_ui(job_slot_index, state, assets, offset),
             JobSlotState::PickingCategory => category_selection_ui(job_slot_index, assets, offset, &layout),
-            JobSlotState::PickingSkill(category) => skill_selection_ui(job_slot_index, category, assets, offset, &layout),
-            JobSlotState::PickingProduct(skill_archetype) => product_selection_ui(job_slot_index, skill_archetype, assets, offset, &layout),
-            JobSlotState::RunningJob(job_instance) => job_card_ui(&state, assets, job_instance, job_slot_index, offset, &layout),
+            JobSlotState::PickingSkill(category) => skill_selection_ui(job_slot_index, category, state, assets, offset, &layout),
+            JobSlotState::PickingProduct(skill_archetype) => product_selection_ui(job_slot_index, skill_archetype, state, assets, offset, &layout),
+            JobSlotState::RunningJob(job_instance) => {
+                let queue = &state.job_slots[job_slot_index].queue;
+                let mut card = job_card_ui(&state, assets, job_instance, job_slot_index, offset, &layout);
+                card.extend(job_queue_ui(queue, job_instance, job_slot_index, state, assets, offset));
+                card.extend(rune_sockets_ui(&state.job_slots[job_slot_index], state, assets, offset));
+                card
+            }
         };
 
         elements.extend(state_specific_elements);
@@ -62,13 +73,162 @@ impl JobSlotState {
     }
 }
 
+/// When the active job of a slot is done and the slot moves on to its next queued job.
+#[derive(Clone, Debug, PartialEq)]
+pub enum StopCondition {
+    Never,
+    ActionsCompleted(i64),
+    ItemAtLeast(Item, i64),
+}
+
+impl StopCondition {
+    pub fn is_met(&self, actions_completed: i64, inventory: &Inventory) -> bool {
+        match self {
+            StopCondition::Never => false,
+            StopCondition::ActionsCompleted(actions) => actions_completed >= *actions,
+            StopCondition::ItemAtLeast(item, amount) => inventory.get_item_amount(item) >= *amount,
+        }
+    }
+
+    /// The goal presets the job card cycles through.
+    pub fn next_preset(&self, product: Item) -> StopCondition {
+        match self {
+            StopCondition::Never => StopCondition::ActionsCompleted(10),
+            StopCondition::ActionsCompleted(10) => StopCondition::ActionsCompleted(50),
+            StopCondition::ActionsCompleted(50) => StopCondition::ActionsCompleted(100),
+            StopCondition::ActionsCompleted(100) => StopCondition::ItemAtLeast(product, 200),
+            _ => StopCondition::Never,
+        }
+    }
+
+    pub fn describe(&self, actions_completed: i64) -> String {
+        match self {
+            StopCondition::Never => "forever".to_string(),
+            StopCondition::ActionsCompleted(actions) => format!("{} / {}", actions_completed, actions),
+            StopCondition::ItemAtLeast(item, amount) => format!("{} >= {}", item.get_name(), amount),
+        }
+    }
+
+    /// Fits the small goal button of a queued job.
+    pub fn describe_short(&self) -> String {
+        match self {
+            StopCondition::Never => "--".to_string(),
+            StopCondition::ActionsCompleted(actions) => format!("x{}", actions),
+            StopCondition::ItemAtLeast(_, amount) => format!("{}+", amount),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct QueuedJob {
+    pub job_archetype: JobArchetype,
+    pub stop_condition: StopCondition,
+}
+
+/// The goal of the job a slot is running and the jobs it switches to afterwards.
+#[derive(Clone, Debug)]
+pub struct JobQueue {
+    pub stop_condition: StopCondition,
+    pub actions_completed: i64,
+    pub upcoming: VecDeque<QueuedJob>,
+}
+
+impl JobQueue {
+    pub fn new() -> Self {
+        Self {
+            stop_condition: StopCondition::Never,
+            actions_completed: 0,
+            upcoming: VecDeque::new(),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct JobSlot {
     pub index: usize,
     pub state: JobSlotState,
+    pub queue: JobQueue,
+    pub sockets: Vec<Option<Rune>>,
 }
 
 impl JobSlot {
+    pub fn new(index: usize, state: JobSlotState) -> Self {
+        Self {
+            index,
+            state,
+            queue: JobQueue::new(),
+            sockets: vec![None; SOCKETS_PER_SLOT],
+        }
+    }
+
+    /// Planks needed to unlock this slot. The second slot is free, later ones get more expensive.
+    pub fn unlock_cost(&self) -> Vec<(Item, i64)> {
+        let index = self.index as i64;
+        let planks = 5 * index * (index - 1).max(0);
+
+        if planks == 0 {
+            return vec![];
+        }
+
+        vec![(Item::Woodworking(WoodWorkingItem::Plank), planks)]
+    }
+
+    /// Replaces the slot state. Starting a new job resets the goal, leaving the job clears the queue.
+    pub fn set_state(&mut self, state: JobSlotState) {
+        match state {
+            JobSlotState::RunningJob(_) => {
+                self.queue.stop_condition = StopCondition::Never;
+                self.queue.actions_completed = 0;
+            }
+            _ => self.queue = JobQueue::new(),
+        }
+
+        self.state = state;
+    }
+
+    /// Gives the inputs the running job already took for its next action back to the inventory.
+    /// Anything that replaces the job calls this first, or the inputs are lost with it.
+    pub fn refund_paid_inputs(&mut self, inventory: &mut Inventory) {
+        let JobSlotState::RunningJob(job_instance) = &mut self.state else {
+            return;
+        };
+
+        if job_instance.has_paid_resources {
+            for (item, amount) in job_instance.job_archetype.get_required_items() {
+                inventory.add_item(item, amount);
+            }
+
+            job_instance.has_paid_resources = false;
+        }
+    }
+
+    /// Switches to the next queued job once the goal is reached, or pauses if nothing is queued.
+    pub fn advance_queue(&mut self, inventory: &mut Inventory) {
+        let JobSlotState::RunningJob(job_instance) = &mut self.state else {
+            return;
+        };
+
+        if !self.queue.stop_condition.is_met(self.queue.actions_completed, inventory) {
+            return;
+        }
+
+        match self.queue.upcoming.pop_front() {
+            Some(next) => {
+                self.refund_paid_inputs(inventory);
+                self.state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+                    job_archetype: next.job_archetype,
+                }));
+                self.queue.stop_condition = next.stop_condition;
+            }
+            None => {
+                job_instance.running = false;
+                self.queue.stop_condition = StopCondition::Never;
+            }
+        }
+
+        self.queue.actions_completed = 0;
+    }
+
     pub fn build_ui(&self, game_state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
         self.state.build_ui(self.index, game_state, assets, offset)
     }
@@ -87,11 +247,36 @@ impl CardLayout {
     }
 }
 
-fn locked_job_slot_ui(index: usize, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
+fn locked_job_slot_ui(slot: &JobSlot, state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
     let mut elements = vec![];
 
     let icon_size = 64.0;
 
+    let cost_text = slot.unlock_cost().iter()
+        .map(|(item, amount)| format!("{} {}", amount, item.get_name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if slot.index >= state.research.slot_limit() {
+        elements.push(UiElement::Text {
+            content: "Needs more research".to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x: offset.x + JOB_CARD_WIDTH / 2.0 - icon_size,
+            y: offset.y + JOB_CARD_HEIGHT / 2.0 + icon_size / 2.0 + 24.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+    } else if !cost_text.is_empty() {
+        elements.push(UiElement::Text {
+            content: format!("Unlock: {}", cost_text),
+            font: assets.fonts.text_bold.clone(),
+            x: offset.x + JOB_CARD_WIDTH / 2.0 - icon_size,
+            y: offset.y + JOB_CARD_HEIGHT / 2.0 + icon_size / 2.0 + 24.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
     // Add image button of a lock in the middle of the card
     elements.push(UiElement::ImgButton {
         rectangle: UiRect::new(
@@ -100,7 +285,7 @@ fn locked_job_slot_ui(index: usize, assets: &Assets, offset: Vec2) -> Vec<UiElem
             icon_size,
             icon_size,
         ),
-        intent: Intent::ChangeJobSlotState(index, JobSlotState::PickingCategory),
+        intent: Intent::UnlockJobSlot(slot.index),
         texture: LockIcon.get_texture(&assets),
         parent_clip: None,
         border_style: BorderStyle::None,
@@ -109,7 +294,7 @@ fn locked_job_slot_ui(index: usize, assets: &Assets, offset: Vec2) -> Vec<UiElem
     elements
 }
 
-fn empty_job_slot_ui(job_slot_index: usize, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
+fn empty_job_slot_ui(job_slot_index: usize, state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
     let mut elements = vec![];
 
     // Add title: Empty Slot
@@ -143,9 +328,24 @@ fn empty_job_slot_ui(job_slot_index: usize, assets: &Assets, offset: Vec2) -> Ve
         border_style: BorderStyle::None,
     });
 
+    elements.push(timeslots_text(state, assets, offset.x + 10.0, offset.y + 10.0 + 32.0 + 40.0 + 30.0 + 24.0));
+
     elements
 }
 
+fn timeslots_text(state: &GameState, assets: &Assets, x: f32, y: f32) -> UiElement {
+    let capacity = state.timeslot_capacity();
+
+    UiElement::Text {
+        content: format!("{} of {} time slots free", capacity - state.timeslots_used(None), capacity),
+        font: assets.fonts.text.clone(),
+        x,
+        y,
+        font_size: 14.0,
+        color: palette::BORDER.get_color(),
+    }
+}
+
 fn category_selection_ui(job_slot_index: usize, assets: &Assets, offset: Vec2, layout: &CardLayout) -> Vec<UiElement> {
     let mut elements = vec![];
 
@@ -214,6 +414,7 @@ fn category_selection_ui(job_slot_index: usize, assets: &Assets, offset: Vec2, l
 fn skill_selection_ui(
     job_slot_index: usize,
     category: &SkillCategory,
+    state: &GameState,
     assets: &Assets,
     offset: Vec2,
     layout: &CardLayout,
@@ -247,13 +448,36 @@ fn skill_selection_ui(
             color: palette::TEXT.get_color(),
         });
 
+        let button_rect = UiRect::new(
+            offset.x + padding_x + (i as f32 * (button_size + button_spacing)),
+            offset.y + 64.0,
+            button_size,
+            button_size,
+        );
+
+        // skills that still need research are shown faded with a lock on top
+        if !state.research.is_skill_available(skill_archetype) {
+            elements.push(UiElement::Image {
+                x: button_rect.x,
+                y: button_rect.y,
+                width: button_rect.w,
+                height: button_rect.h,
+                texture: skill_archetype.get_icon_texture(assets),
+                color: Color { a: 0.3, ..PaletteC::White.get_color() },
+            });
+            elements.push(UiElement::Image {
+                x: button_rect.x + button_rect.w / 4.0,
+                y: button_rect.y + button_rect.h / 4.0,
+                width: button_rect.w / 2.0,
+                height: button_rect.h / 2.0,
+                texture: LockIcon.get_texture(assets),
+                color: PaletteC::White.get_color(),
+            });
+            continue;
+        }
+
         elements.push(UiElement::ImgButton {
-            rectangle: UiRect::new(
-                offset.x + padding_x + (i as f32 * (button_size + button_spacing)),
-                offset.y + 64.0,
-                button_size,
-                button_size,
-            ),
+            rectangle: button_rect,
             intent: Intent::ChangeJobSlotState(
                 job_slot_index,
                 JobSlotState::PickingProduct(skill_archetype.clone()),
@@ -270,6 +494,7 @@ fn skill_selection_ui(
 fn product_selection_ui(
     job_slot_index: usize,
     skill_archetype: &SkillArchetype,
+    state: &GameState,
     assets: &Assets,
     offset: Vec2,
     layout: &CardLayout,
@@ -285,18 +510,31 @@ fn product_selection_ui(
         color: palette::TEXT.get_color(),
     });
 
-    for (i, job_archetype) in skill_archetype.get_job_archetypes().iter().enumerate() {
+    // three rows fit on a card, more products wrap into additional columns
+    let job_archetypes: Vec<JobArchetype> = skill_archetype.get_job_archetypes().into_iter()
+        .filter(|job_archetype| state.is_job_available(job_archetype))
+        .collect();
+    let rows = 3;
+    let columns = job_archetypes.len().div_ceil(rows).max(1);
+    let button_width = (JOB_CARD_WIDTH - layout.padding_x * 2.0 - layout.spacing_x * (columns as f32 - 1.0)) / columns as f32;
+
+    for (i, job_archetype) in job_archetypes.iter().enumerate() {
+        let column = i / rows;
+        let row = i % rows;
+        let fits = state.fits_timeslots(job_slot_index, job_archetype);
+        let cost = job_archetype.timeslot_cost();
+
         elements.push(UiElement::RectButton {
             rectangle: UiRect {
-                x: offset.x + layout.padding_x,
-                y: offset.y + 60.0 + (i as f32 * 40.0),
-                w: JOB_CARD_WIDTH - layout.padding_x * 2.0,
+                x: offset.x + layout.padding_x + column as f32 * (button_width + layout.spacing_x),
+                y: offset.y + 60.0 + (row as f32 * 40.0),
+                w: button_width,
                 h: 30.0,
             },
             font_size: 16.0,
             font: assets.fonts.text.clone(),
-            text: job_archetype.get_name().clone(),
-            background_color: palette::BUTTON_BACKGROUND.get_color(),
+            text: if cost > 1 { format!("{} ({} slots)", job_archetype.get_name(), cost) } else { job_archetype.get_name().clone() },
+            background_color: if fits { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
             text_color: palette::BUTTON_TEXT.get_color(),
             intent: Intent::ChangeJobSlotState(
                 job_slot_index,
@@ -380,15 +618,19 @@ fn job_card_ui(
         border_style: BorderStyle::Solid,
     });
 
-    // Job Animation Image
+    // Job Animation Image, clicking it helps the job along
     let image_padding = 6.0;
-    elements.push(UiElement::Image {
-        x: image_x + image_padding,
-        y: image_y + image_padding,
-        width: image_width - image_padding * 2.0,
-        height: image_height - image_padding * 2.0,
+    elements.push(UiElement::ImgButton {
+        rectangle: UiRect {
+            x: image_x + image_padding,
+            y: image_y + image_padding,
+            w: image_width - image_padding * 2.0,
+            h: image_height - image_padding * 2.0,
+        },
+        intent: Intent::ClickJob(job_slot_id),
         texture: chosen_image.clone(),
-        color: PaletteC::White.get_color(),
+        parent_clip: clip,
+        border_style: BorderStyle::None,
     });
 
     let button_width = 30.0;
@@ -414,7 +656,23 @@ fn job_card_ui(
         });
     }
 
-    // Delete Button
+    // Mana Potion button, charges hyper mode or extends it while it is active
+    if state.inventory.get_item_amount(&Item::ManaPotion) > 0 && (job.hyper_mode.is_enabled || !job.hyper_mode.has_enough_actions()) {
+        elements.push(UiElement::ImgButton {
+            rectangle: UiRect {
+                x: offset.x + card_width - right_side_width - card_padding_x - right_side_width - card_spacing_x - button_width - button_spacing,
+                y: offset.y + card_padding_y,
+                w: button_width,
+                h: button_width,
+            },
+            intent: Intent::UseItem(Item::ManaPotion, Some(job_slot_id)),
+            texture: Item::ManaPotion.get_texture(assets),
+            parent_clip: clip,
+            border_style: BorderStyle::None,
+        });
+    }
+
+    // Delete Button, the slot was paid for so it stays unlocked
     elements.push(UiElement::RectButton {
         rectangle: UiRect {
             x: offset.x + card_width - button_width - card_padding_x,
@@ -428,7 +686,7 @@ fn job_card_ui(
         text: "x".to_string(),
         background_color: palette::BUTTON_BACKGROUND.get_color(),
         text_color: palette::BUTTON_TEXT.get_color(),
-        intent: Intent::ChangeJobSlotState(job_slot_id, JobSlotState::Locked),
+        intent: Intent::ChangeJobSlotState(job_slot_id, JobSlotState::Empty),
         border_style: BorderStyle::Solid,
     });
 
@@ -470,15 +728,58 @@ fn job_card_ui(
         color: PaletteC::White.get_color(),
     });
 
-    // Draw Product Pill below the Product Image
+    // Equipped tool for the skill, or a button to equip one from the inventory
+    let tool_x = offset.x + card_width - right_side_width - card_padding_x + 4.0;
+    let tool_width = right_side_width - 8.0;
+    let skill_type = job.job_archetype.get_skill_type();
+
+    if let Some(equipped) = state.equipment.get_tool_for_skill(&skill_type) {
+        elements.push(UiElement::Text {
+            content: equipped.tool.get_name().to_string(),
+            font: assets.fonts.text.clone(),
+            x: tool_x,
+            y: image_y + 16.0,
+            font_size: 12.0,
+            color: color_primary,
+        });
+
+        elements.push(UiElement::ProgressBar {
+            x: tool_x,
+            y: image_y + 22.0,
+            width: tool_width,
+            height: 6.0,
+            progress: equipped.durability as f64 / TOOL_DURABILITY as f64,
+            background_color: palette::BAR_BACKGROUND.get_color(),
+            foreground_color: palette::PROGRESS_COLOR.get_color(),
+            border_style: BorderStyle::None,
+        });
+    } else if let Some(tool) = Tool::for_skill(&skill_type).filter(|tool| state.inventory.get_item_amount(&Item::Tool(*tool)) > 0) {
+        elements.push(UiElement::RectButton {
+            rectangle: UiRect::new(tool_x, image_y + 6.0, tool_width, 24.0),
+            font: assets.fonts.text.clone(),
+            parent_clip: clip,
+            font_size: 12.0,
+            text: format!("+ {}", tool.get_name()),
+            background_color: palette::BUTTON_BACKGROUND.get_color(),
+            text_color: palette::BUTTON_TEXT.get_color(),
+            intent: Intent::EquipTool(tool),
+            border_style: BorderStyle::Solid,
+        });
+    }
+
+    // Draw Product Pill below the Product Image, in red once storage is full
+    let product = job.job_archetype.get_product();
+    let product_amount = state.inventory.get_item_amount(&product);
+    let storage_full = state.village.storage_room(&product, product_amount) < 1;
+
     elements.extend(
         number_pill(
             offset.x + card_width - right_side_width - card_padding_x + right_side_width / 2.0 - 24.0 / 2.0,
             image_y + image_height - 14.0 / 2.0 - 2.0,
             24.0,
             14.0,
-            state.inventory.get_item_amount(&job.job_archetype.get_product()),
-            None,
+            product_amount,
+            if storage_full { Some(PaletteC::Coral.get_color()) } else { None },
             assets.fonts.mono.clone()
         )
     );
@@ -490,7 +791,8 @@ fn job_card_ui(
 
     let required_items = job.job_archetype.get_required_items();
     let item_slots = required_items.len();
-    let empty_slots = 4 - item_slots;
+    let fuel_slots = if job.job_archetype.uses_fuel() { 1 } else { 0 };
+    let empty_slots = 4 - item_slots - fuel_slots;
     let resource_y = offset.y + card_padding_y + 100.0;
 
     // Draw required resources
@@ -568,6 +870,43 @@ fn job_card_ui(
         });
     }
 
+    // Fuel slot in the last resource position, red while the job runs slower without fuel
+    if fuel_slots > 0 {
+        let resource_x = inner_x + 3.0 * (resource_icon_size + resource_icon_spacing);
+        let fuel_amount = state.inventory.get_item_amount(&FUEL_ITEM);
+
+        elements.push(UiElement::Rectangle {
+            x: resource_x,
+            y: resource_y,
+            width: resource_icon_size,
+            height: resource_icon_size,
+            color: if fuel_amount > 0 { palette::IMAGE_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+            border_style: BorderStyle::Solid,
+        });
+
+        let resource_inner_size = resource_icon_size - resource_icon_padding * 2.0;
+        elements.push(UiElement::Image {
+            x: resource_x + resource_icon_padding,
+            y: resource_y + resource_icon_padding,
+            width: resource_inner_size,
+            height: resource_inner_size,
+            texture: if fuel_amount > 0 { FUEL_ITEM.get_texture(assets) } else { WoodBurner.get_texture(assets) },
+            color: PaletteC::White.get_color(),
+        });
+
+        elements.extend(
+            number_pill(
+                resource_x + resource_icon_size / 2.0 - 24.0 / 2.0,
+                resource_y - 14.0 / 2.0 - 2.0,
+                24.0,
+                14.0,
+                fuel_amount,
+                None,
+                assets.fonts.mono.clone()
+            )
+        );
+    }
+
     // Draw Skill instance level up progress bar
     let progress_bar_height = 12.0;
     elements.push(UiElement::ProgressBar {
@@ -605,13 +944,18 @@ fn job_card_ui(
         border_style: BorderStyle::Solid,
     });
 
-    // Job Type and Level
+    // Job Type and Level, plus whoever works the slot
+    let worker_text = state.workers.get_assigned(job_slot_id)
+        .map(|worker| format!(" - {} Lv. {}", worker.name, worker.experience.level))
+        .unwrap_or_default();
+
     elements.push(UiElement::Text {
         content: format!(
-            "Lv. {} ({} / {})",
+            "Lv. {} ({} / {}){}",
             job_archetype_instance.action_counter.level,
             job_archetype_instance.action_counter.actions_done_current_level,
             job_archetype_instance.action_counter.actions_to_next_level(),
+            worker_text,
         ),
         font: assets.fonts.text.clone(),
         x: offset.x + card_padding_x,
@@ -620,5 +964,213 @@ fn job_card_ui(
         color: color_secondary,
     });
 
+    // What is left of the grove or vein the job gathers from, veins can be replaced by prospecting
+    if let Some(node) = state.resource_nodes.get_node(&job.job_archetype) {
+        let is_vein = node.kind() == NodeKind::Vein;
+        let prospect_width = if is_vein { 70.0 } else { 0.0 };
+        let prospecting = state.resource_nodes.prospecting.as_ref()
+            .filter(|prospecting| prospecting.job_archetype == job.job_archetype);
+
+        let node_text = match prospecting {
+            Some(prospecting) => format!("Prospecting {}", pretty_duration(prospecting.remaining_seconds)),
+            None if node.is_exhausted() => format!("{} exhausted", node.kind().get_name()),
+            None => format!("{} {:.0} / {:.0}", node.kind().get_name(), node.remaining.floor(), node.capacity),
+        };
+
+        elements.push(UiElement::Text {
+            content: node_text,
+            font: assets.fonts.text.clone(),
+            x: inner_x,
+            y: offset.y + card_padding_y + 56.0,
+            font_size: 12.0,
+            color: if node.is_exhausted() { PaletteC::Coral.get_color() } else { color_secondary },
+        });
+
+        elements.push(UiElement::ProgressBar {
+            x: inner_x,
+            y: offset.y + card_padding_y + 62.0,
+            width: inner_width - prospect_width - if is_vein { 4.0 } else { 0.0 },
+            height: 6.0,
+            progress: node.health(),
+            background_color: palette::BAR_BACKGROUND.get_color(),
+            foreground_color: if node.is_exhausted() { PaletteC::Coral.get_color() } else { palette::PROGRESS_COLOR.get_color() },
+            border_style: BorderStyle::None,
+        });
+
+        if is_vein {
+            elements.push(UiElement::RectButton {
+                rectangle: UiRect::new(inner_x + inner_width - prospect_width, offset.y + card_padding_y + 46.0, prospect_width, 22.0),
+                font: assets.fonts.text.clone(),
+                parent_clip: clip,
+                font_size: 12.0,
+                text: format!("Prospect {}", PROSPECT_COST),
+                background_color: if can_prospect(state, &job.job_archetype) { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+                text_color: palette::BUTTON_TEXT.get_color(),
+                intent: Intent::Prospect(job.job_archetype),
+                border_style: BorderStyle::Solid,
+            });
+        }
+    }
+
     elements
-}
\ No newline at end of file
+}
+
+fn rune_sockets_ui(slot: &JobSlot, state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    let socket_size = 24.0;
+    let socket_spacing = 4.0;
+    // bottom left corner of the animation panel
+    let socket_x = offset.x + 16.0 + 4.0;
+    let socket_y = offset.y + JOB_CARD_HEIGHT - 16.0 - socket_size - 4.0;
+
+    let socketable_runes: Vec<Rune> = Rune::iter().filter(|rune| rune.is_socketable()).collect();
+
+    for (i, socketed) in slot.sockets.iter().enumerate() {
+        let rect = UiRect::new(socket_x + i as f32 * (socket_size + socket_spacing), socket_y, socket_size, socket_size);
+
+        // clicking cycles through the runes in the inventory and back to an empty socket
+        let candidates = match socketed {
+            None => socketable_runes.iter().collect::<Vec<_>>(),
+            Some(rune) => socketable_runes.iter().skip_while(|other| *other != rune).skip(1).collect(),
+        };
+        let next_rune = candidates.into_iter()
+            .find(|rune| state.inventory.get_item_amount(&Item::Rune(**rune)) > 0)
+            .copied();
+
+        let intent = Intent::SetSocket(slot.index, i, next_rune);
+
+        match socketed {
+            Some(rune) => {
+                elements.push(UiElement::Rectangle {
+                    x: rect.x,
+                    y: rect.y,
+                    width: rect.w,
+                    height: rect.h,
+                    color: palette::IMAGE_BACKGROUND.get_color(),
+                    border_style: BorderStyle::Solid,
+                });
+
+                elements.push(UiElement::ImgButton {
+                    rectangle: rect,
+                    intent,
+                    texture: rune.get_texture(assets),
+                    parent_clip: None,
+                    border_style: BorderStyle::None,
+                });
+            }
+            None => {
+                elements.push(UiElement::RectButton {
+                    rectangle: rect,
+                    font_size: 14.0,
+                    font: assets.fonts.text_bold.clone(),
+                    text: "+".to_string(),
+                    background_color: palette::IMAGE_BACKGROUND.get_color(),
+                    text_color: palette::TEXT.get_color(),
+                    intent,
+                    parent_clip: None,
+                    border_style: BorderStyle::Dotted,
+                });
+            }
+        }
+    }
+
+    let bonus = describe_modifiers(&socket_modifiers(&slot.sockets));
+
+    if !bonus.is_empty() {
+        elements.push(UiElement::Text {
+            content: bonus,
+            font: assets.fonts.text.clone(),
+            x: socket_x + SOCKETS_PER_SLOT as f32 * (socket_size + socket_spacing) + 4.0,
+            y: socket_y + socket_size - 6.0,
+            font_size: 12.0,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    elements
+}
+
+fn job_queue_ui(queue: &JobQueue, job_instance: &JobInstance, job_slot_index: usize, state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    let button_height = 18.0;
+    let button_y = offset.y + 38.0;
+    let goal_width = 118.0;
+    let next_goal_width = 36.0;
+    let next_width = 100.0;
+    let next_goal_x = offset.x + JOB_CARD_WIDTH - 16.0 - next_goal_width;
+    let next_x = next_goal_x - 4.0 - next_width;
+    let goal_x = next_x - 4.0 - goal_width;
+
+    // Goal of the active job, clicking cycles through the presets
+    elements.push(UiElement::RectButton {
+        rectangle: UiRect::new(goal_x, button_y, goal_width, button_height),
+        font_size: 12.0,
+        font: assets.fonts.text.clone(),
+        text: format!("Goal: {}", queue.stop_condition.describe(queue.actions_completed)),
+        background_color: palette::BUTTON_BACKGROUND.get_color(),
+        text_color: palette::BUTTON_TEXT.get_color(),
+        intent: Intent::SetStopCondition(
+            job_slot_index,
+            queue.stop_condition.next_preset(job_instance.job_archetype.get_product()),
+        ),
+        parent_clip: None,
+        border_style: BorderStyle::None,
+    });
+
+    // Next queued job, clicking cycles through all researched jobs and back to nothing
+    let all_jobs: Vec<JobArchetype> = JobArchetype::all().into_iter()
+        .filter(|job| state.is_job_available(job))
+        .collect();
+    let next_job = queue.upcoming.front().map(|queued| queued.job_archetype);
+    let cycled_job = match next_job {
+        None => all_jobs.first().copied(),
+        Some(job) => all_jobs.iter().skip_while(|other| **other != job).nth(1).copied(),
+    };
+
+    let mut cycled_queue: Vec<QueuedJob> = queue.upcoming.iter().cloned().collect();
+    match (cycled_job, cycled_queue.is_empty()) {
+        (Some(job_archetype), true) => cycled_queue.push(QueuedJob { job_archetype, stop_condition: StopCondition::Never }),
+        (Some(job_archetype), false) => cycled_queue[0].job_archetype = job_archetype,
+        (None, _) => { cycled_queue.remove(0); }
+    }
+
+    let next_text = match next_job {
+        None => "Next: -".to_string(),
+        Some(job) if queue.upcoming.len() > 1 => format!("Next: {} (+{})", job.get_name(), queue.upcoming.len() - 1),
+        Some(job) => format!("Next: {}", job.get_name()),
+    };
+
+    elements.push(UiElement::RectButton {
+        rectangle: UiRect::new(next_x, button_y, next_width, button_height),
+        font_size: 12.0,
+        font: assets.fonts.text.clone(),
+        text: next_text,
+        background_color: palette::BUTTON_BACKGROUND.get_color(),
+        text_color: palette::BUTTON_TEXT.get_color(),
+        intent: Intent::SetJobQueue(job_slot_index, cycled_queue),
+        parent_clip: None,
+        border_style: BorderStyle::None,
+    });
+
+    // Goal of the next queued job, cycling through the same presets
+    if let Some(next) = queue.upcoming.front() {
+        let mut goal_queue: Vec<QueuedJob> = queue.upcoming.iter().cloned().collect();
+        goal_queue[0].stop_condition = next.stop_condition.next_preset(next.job_archetype.get_product());
+
+        elements.push(UiElement::RectButton {
+            rectangle: UiRect::new(next_goal_x, button_y, next_goal_width, button_height),
+            font_size: 12.0,
+            font: assets.fonts.text.clone(),
+            text: next.stop_condition.describe_short(),
+            background_color: palette::BUTTON_BACKGROUND.get_color(),
+            text_color: palette::BUTTON_TEXT.get_color(),
+            intent: Intent::SetJobQueue(job_slot_index, goal_queue),
+            parent_clip: None,
+            border_style: BorderStyle::None,
+        });
+    }
+
+    elements
+}
@@ -7,4 +7,27 @@ pub mod assets;
 pub mod skill;
 pub mod counts_actions;
 pub mod job_slot;
-pub mod awesome;
\ No newline at end of file
+pub mod awesome;
+pub mod rates;
+pub mod optimizer;
+pub mod automation;
+pub mod save;
+pub mod equipment;
+pub mod buffs;
+pub mod shop;
+pub mod rng;
+pub mod market;
+pub mod achievements;
+pub mod orders;
+pub mod prestige;
+pub mod research;
+pub mod village;
+pub mod runes;
+pub mod workers;
+pub mod villagers;
+pub mod territory;
+pub mod expeditions;
+pub mod combat;
+pub mod resource_nodes;
+pub mod spoilage;
+pub mod toasts;
@@ -14,16 +14,59 @@ pub mod skill;
 pub mod counts_actions;
 pub mod job_slot;
 pub mod awesome;
+pub mod rates;
+pub mod optimizer;
+pub mod automation;
+pub mod save;
+pub mod equipment;
+pub mod buffs;
+pub mod shop;
+pub mod rng;
+pub mod market;
+pub mod achievements;
+pub mod orders;
+pub mod prestige;
+pub mod research;
+pub mod village;
+pub mod runes;
+pub mod workers;
+pub mod villagers;
+pub mod territory;
+pub mod expeditions;
+pub mod combat;
+pub mod resource_nodes;
+pub mod spoilage;
+pub mod toasts;
 
 use crate::assets::{load_assets, Assets};
 use crate::awesome::nine_patch::draw_nine_patch;
 use crate::draw::{draw, number_pill, pill, BorderStyle, UiElement};
-use crate::game::{GameState, GameTab, Intent, MouseInput, UiRect};
+use crate::game::{pretty_duration, GameState, GameTab, Intent, Item, MouseInput, UiRect};
 use crate::job::{JobArchetype, LumberingJobArchetype};
 use crate::job::{JobInstance, JobParameters};
 use crate::job_slot::{JobSlot, JOB_CARD_HEIGHT, JOB_CARD_SPACING_OUTER, WINDOW_PADDING};
 use crate::job_slot::JobSlotState;
 use crate::palette::PaletteC;
+use crate::rates::build_rates_overlay;
+use crate::automation::build_automation_ui;
+use crate::achievements::build_achievements_ui;
+use crate::market::Market;
+use crate::orders::build_orders_ui;
+use crate::prestige::build_prestige_ui;
+use crate::research::build_research_ui;
+use crate::village::{build_village_ui, Building};
+use crate::workers::build_workers_ui;
+use crate::villagers::build_villagers_ui;
+use crate::territory::build_territory_ui;
+use crate::expeditions::build_expeditions_ui;
+use crate::combat::build_combat_ui;
+use crate::spoilage::{aging_rate, PERISHABLES};
+use crate::toasts::build_toasts;
+use crate::rng::Rng;
+use crate::shop::build_shop_ui;
+use crate::save::{load_game, offline_seconds, save_game};
+
+const AUTOSAVE_INTERVAL_SECONDS: f64 = 10.0;
 
 pub fn get_mouse_buttons(check: fn(MouseButton) -> bool) -> Vec<MouseButton> {
     vec![MouseButton::Left, MouseButton::Right, MouseButton::Middle]
@@ -38,28 +81,14 @@ async fn main() {
     request_new_screen_size(1280., 720.0);
     set_default_filter_mode(FilterMode::Linear);
 
-    let mut state = GameState::new();
+    let mut state = load_game().unwrap_or_else(new_game);
+    state.catch_up_offline(offline_seconds());
     let mut is_fullscreen = false;
     let mut show_debug = false;
+    let mut last_save = now();
 
     let assets: Assets = load_assets().await;
 
-    state.job_slots[0] = JobSlot {
-        index: 0,
-        state: JobSlotState::RunningJob(JobInstance::new(
-            JobParameters {
-                job_archetype: JobArchetype::Lumbering(
-                    LumberingJobArchetype::Craftwood
-                )
-            },
-        )),
-    };
-
-    // Example for using quad-storage
-    // let storage = &mut quad_storage::STORAGE.lock().unwrap();
-    // storage.set("test", &format!("{}", now()));
-    // let value = storage.get("test").unwrap();
-
     loop {
         let frame_start = now();
         let dt = get_frame_time();
@@ -105,10 +134,34 @@ async fn main() {
         state.game_meta.raw_fps = 1.0 / elapsed;
         state.game_meta.effective_fps = get_fps() as f64;
 
+        if now() - last_save >= AUTOSAVE_INTERVAL_SECONDS {
+            save_game(&state);
+            last_save = now();
+        }
+
         next_frame().await;
     }
 }
 
+fn new_game() -> GameState {
+    let mut state = GameState::new();
+    state.market = Market::new(now().to_bits());
+    state.rng = Rng::new(now().to_bits().rotate_left(32));
+
+    state.job_slots[0] = JobSlot::new(
+        0,
+        JobSlotState::RunningJob(JobInstance::new(
+            JobParameters {
+                job_archetype: JobArchetype::Lumbering(
+                    LumberingJobArchetype::Craftwood
+                )
+            },
+        )),
+    );
+
+    state
+}
+
 fn build_ui_elements(state: &GameState, assets: &Assets, resolution_offset: Vec2, show_debug: bool) -> Vec<UiElement> {
     let mut all_elements: Vec<UiElement> = vec![];
 
@@ -117,14 +170,57 @@ fn build_ui_elements(state: &GameState, assets: &Assets, resolution_offset: Vec2
     match &state.game_tab {
         GameTab::Jobs => {
             all_elements.extend(build_inventory_elements(&state, &assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, JOB_CARD_WIDTH, JOB_CARD_HEIGHT * 3.0 + JOB_CARD_SPACING_OUTER * 2.0)));
-            all_elements.extend(state.get_job_slot_ui(&state, &assets, Vec2::new(WINDOW_PADDING + resolution_offset.x + JOB_CARD_WIDTH + JOB_CARD_SPACING_OUTER, 100.0 + resolution_offset.y)));
+            let slots_offset = Vec2::new(WINDOW_PADDING + resolution_offset.x + JOB_CARD_WIDTH + JOB_CARD_SPACING_OUTER, 100.0 + resolution_offset.y);
+            if state.show_rates_overlay {
+                all_elements.extend(build_rates_overlay(state, assets, UiRect::new(slots_offset.x, slots_offset.y, JOB_CARD_WIDTH * 2.0 + JOB_CARD_SPACING_OUTER, JOB_CARD_HEIGHT * 3.0 + JOB_CARD_SPACING_OUTER * 2.0)));
+            } else {
+                all_elements.extend(state.get_job_slot_ui(&state, &assets, slots_offset));
+            }
         }
         GameTab::Inventory => {
 
+        }
+        GameTab::Automation => {
+            all_elements.extend(build_automation_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Shop => {
+            all_elements.extend(build_shop_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Orders => {
+            all_elements.extend(build_orders_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Prestige => {
+            all_elements.extend(build_prestige_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Research => {
+            all_elements.extend(build_research_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Village => {
+            all_elements.extend(build_village_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Workers => {
+            all_elements.extend(build_workers_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Villagers => {
+            all_elements.extend(build_villagers_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Territory => {
+            all_elements.extend(build_territory_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Expeditions => {
+            all_elements.extend(build_expeditions_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Combat => {
+            all_elements.extend(build_combat_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
+        }
+        GameTab::Achievements => {
+            all_elements.extend(build_achievements_ui(state, assets, UiRect::new(WINDOW_PADDING + resolution_offset.x, 100.0 + resolution_offset.y, 1280.0 - WINDOW_PADDING * 2.0, 720.0 - 100.0 - WINDOW_PADDING)));
         }
         _default => ()
     }
 
+    all_elements.extend(build_toasts(state, assets, resolution_offset));
+
     if show_debug {
         all_elements.extend(build_debug_elements(&state, &assets, UiRect::new(700.0, WINDOW_PADDING, 200.0, 40.0)));
         all_elements.extend(get_cheat_buttons(&assets, UiRect::new(600.0, 30.0, 400.0, 40.0)));
@@ -141,15 +237,32 @@ fn build_menu_ui(state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElem
         GameTab::Inventory,
         GameTab::Skills,
         GameTab::Stats,
+        GameTab::Automation,
+        GameTab::Research,
+        GameTab::Village,
+        GameTab::Territory,
+        GameTab::Workers,
+        GameTab::Expeditions,
+        GameTab::Combat,
+        GameTab::Villagers,
+        GameTab::Shop,
+        GameTab::Orders,
+        GameTab::Achievements,
+        GameTab::Prestige,
     ];
 
+    // a second row of smaller tabs once they don't fit next to the buffs anymore
+    let tabs_per_row = 9;
+    let rows = game_tabs.len().div_ceil(tabs_per_row);
+    let tab_height = if rows > 1 { 34.0 } else { 50.0 };
+
     game_tabs.iter().enumerate().for_each(|(i, tab)| {
         elements.push(UiElement::RectButton {
             rectangle: UiRect {
-                x: offset.x + 25.0 + (i * 136) as f32,
-                y: offset.y + 15.0,
-                w: 130.0,
-                h: 50.0,
+                x: offset.x + 25.0 + ((i % tabs_per_row) * 100) as f32,
+                y: offset.y + 15.0 - (rows as f32 - 1.0) * 5.0 + (i / tabs_per_row) as f32 * (tab_height + 6.0),
+                w: 94.0,
+                h: tab_height,
             },
             font: assets.fonts.mono.clone(),
             intent: Intent::SelectGameTab(tab.clone()),
@@ -166,6 +279,40 @@ fn build_menu_ui(state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElem
         });
     });
 
+    // Active buffs and their remaining time
+    for (i, buff) in state.buffs.active.iter().enumerate() {
+        elements.push(UiElement::Text {
+            content: format!("{} {}", buff.kind.get_name(), pretty_duration(buff.remaining_seconds)),
+            font: assets.fonts.mono.clone(),
+            x: offset.x + 1280.0 - 25.0 - 130.0 - 10.0 - 160.0,
+            y: offset.y + 32.0 + i as f32 * 20.0,
+            font_size: 16.0,
+            color: palette::PILL_TEXT_COLOR.get_color(),
+        });
+    }
+
+    // Toggle for the production rates overlay
+    elements.push(UiElement::RectButton {
+        rectangle: UiRect {
+            x: offset.x + 1280.0 - 25.0 - 130.0,
+            y: offset.y + 15.0,
+            w: 130.0,
+            h: 50.0,
+        },
+        font: assets.fonts.mono.clone(),
+        intent: Intent::ToggleRatesOverlay,
+        text: "Rates".to_string(),
+        font_size: 16.0,
+        background_color: if state.show_rates_overlay {
+            Color::from_rgba(255, 200, 51, 255)
+        } else {
+            palette::BUTTON_BACKGROUND.get_color()
+        },
+        text_color: palette::BUTTON_TEXT.get_color(),
+        parent_clip: None,
+        border_style: BorderStyle::Solid,
+    });
+
     elements
 }
 
@@ -330,21 +477,50 @@ pub fn build_inventory_elements(state: &GameState, assets: &Assets, rect: UiRect
             border_style: BorderStyle::Solid,
         });
 
-        // item texture
-        elements.push(UiElement::Image {
-            texture: texture.clone(),
-            x: pos_x + 2.0,
-            y: pos_y + 2.0,
-            width: item_size - 4.0,
-            height: item_size - 4.0,
-            color: WHITE,
-        });
+        // item texture, consumables can be used by clicking them
+        if *item_name == Item::Sandwich {
+            elements.push(UiElement::ImgButton {
+                rectangle: UiRect::new(pos_x + 2.0, pos_y + 2.0, item_size - 4.0, item_size - 4.0),
+                intent: Intent::UseItem(*item_name, None),
+                texture: texture.clone(),
+                parent_clip: None,
+                border_style: BorderStyle::None,
+            });
+        } else {
+            elements.push(UiElement::Image {
+                texture: texture.clone(),
+                x: pos_x + 2.0,
+                y: pos_y + 2.0,
+                width: item_size - 4.0,
+                height: item_size - 4.0,
+                color: WHITE,
+            });
+        }
 
         let pill_width = 20.0;
         let pill_height = 12.0;
         let pill_x = pos_x + (item_size - pill_width) / 2.0;
         let pill_y = pos_y + item_size - pill_height / 2.0;
         elements.extend(number_pill(pill_x, pill_y, pill_width, pill_height, *item_count, None, assets.fonts.mono.clone()));
+
+        // how many go off at the next aging, in red on top of the item
+        let spoiling = state.spoilage.spoiling_soon(item_name);
+        if spoiling > 0 {
+            elements.extend(number_pill(pill_x, pos_y - pill_height / 2.0, pill_width, pill_height, spoiling, Some(PaletteC::Coral.get_color()), assets.fonts.mono.clone()));
+        }
+    }
+
+    if PERISHABLES.iter().any(|item| state.spoilage.spoiling_soon(item) > 0) {
+        let rate = aging_rate(state.village.get_level(&Building::Cellar));
+
+        elements.push(UiElement::Text {
+            content: format!("Red counts spoil in {}, a cellar or cooking keeps food longer", pretty_duration(state.spoilage.seconds_until_aging(rate))),
+            font: assets.fonts.text.clone(),
+            x: rect.x + padding,
+            y: rect.y + rect.h - padding,
+            font_size: 14.0,
+            color: PaletteC::Coral.get_color(),
+        });
     }
 
     elements
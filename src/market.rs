@@ -0,0 +1,187 @@
+use crate::equipment::Tool;
+use crate::game::{Item, WoodItem, WoodWorkingItem};
+use crate::rng::Rng;
+use crate::runes::Rune;
+use std::collections::{HashMap, VecDeque};
+
+pub const MARKET_TICK_SECONDS: f64 = 10.0;
+pub const PRICE_HISTORY_LENGTH: usize = 60;
+
+/// Every unit sold lowers the price of the next one by half a percent, down to a quarter of the base price.
+const SATURATION_PER_UNIT: f64 = 0.995;
+const MIN_SATURATION: f64 = 0.25;
+
+/// Share of the gap to the base price that is left after each tick.
+const RECOVERY_PER_TICK: f64 = 0.97;
+
+const SPIKE_CHANCE_PER_TICK: f64 = 0.005;
+const SPIKE_TICKS: i64 = 18;
+const MIN_SPIKE_FACTOR: f64 = 1.5;
+
+/// Spikes stay below this many times the base price. Discounted shop prices never go under it, so
+/// buying and selling right back can't make coins.
+pub const MAX_SPIKE_FACTOR: f64 = 2.4;
+
+/// What the market pays for one of an item when it isn't saturated. Coins can't be sold.
+pub fn base_price(item: &Item) -> Option<i64> {
+    let price = match item {
+        Item::Coin => return None,
+        Item::Wood(WoodItem::Craftwood) => 1,
+        Item::Wood(WoodItem::Graintree) => 2,
+        Item::Wood(WoodItem::Kindlewood) => 1,
+        Item::Woodworking(WoodWorkingItem::Plank) => 3,
+        Item::Woodworking(WoodWorkingItem::Beam) => 10,
+        Item::Woodworking(WoodWorkingItem::Crate) => 20,
+        Item::IronOre => 2,
+        Item::Herb => 1,
+        Item::Meat => 2,
+        Item::Berry => 1,
+        Item::IronBar => 5,
+        Item::Sandwich => 15,
+        Item::ManaPotion => 5,
+        Item::Tool(Tool::Knife) => 25,
+        Item::Tool(Tool::Mortar) => 30,
+        Item::Tool(_) => 35,
+        Item::Rune(Rune::Blank) => 15,
+        Item::Rune(Rune::Time | Rune::Heart) => 40,
+        Item::Rune(Rune::GreaterTime | Rune::GreaterHeart) => 150,
+    };
+
+    Some(price)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarketPrice {
+    pub saturation: f64,
+    pub spike_factor: f64,
+    pub spike_ticks_left: i64,
+    pub history: VecDeque<f64>,
+}
+
+impl MarketPrice {
+    pub fn new() -> Self {
+        Self {
+            saturation: 1.0,
+            spike_factor: 1.0,
+            spike_ticks_left: 0,
+            history: VecDeque::new(),
+        }
+    }
+
+    pub fn spike_multiplier(&self) -> f64 {
+        if self.spike_ticks_left > 0 {
+            self.spike_factor
+        } else {
+            1.0
+        }
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.saturation * self.spike_multiplier()
+    }
+}
+
+/// Sell prices that drop with the volume the player sells, recover over time and spike now and then.
+/// All randomness comes from the seeded rng, so the same seed and the same sales give the same prices.
+pub struct Market {
+    pub rng: Rng,
+    pub prices: HashMap<Item, MarketPrice>,
+    pub tick_accumulator: f64,
+    pub coin_accumulator: f64,
+    pub chart_item: Option<Item>,
+}
+
+impl Market {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            prices: Item::all().into_iter()
+                .filter(|item| base_price(item).is_some())
+                .map(|item| (item, MarketPrice::new()))
+                .collect(),
+            tick_accumulator: 0.0,
+            coin_accumulator: 0.0,
+            chart_item: None,
+        }
+    }
+
+    pub fn get_price(&self, item: &Item) -> Option<f64> {
+        let base = base_price(item)? as f64;
+        let multiplier = self.prices.get(item).map(|price| price.multiplier()).unwrap_or(1.0);
+
+        Some(base * multiplier)
+    }
+
+    pub fn is_spiking(&self, item: &Item) -> bool {
+        self.prices.get(item).is_some_and(|price| price.spike_ticks_left > 0)
+    }
+
+    /// Coins the given amount would fetch right now, including the drop while selling.
+    pub fn quote(&self, item: &Item, amount: i64) -> f64 {
+        let (Some(base), Some(price)) = (base_price(item), self.prices.get(item)) else {
+            return 0.0;
+        };
+
+        let mut saturation = price.saturation;
+        let mut total = 0.0;
+
+        for sold in 0..amount {
+            if saturation <= MIN_SATURATION {
+                // the rest all go at the lowest price
+                return total + (amount - sold) as f64 * base as f64 * MIN_SATURATION * price.spike_multiplier();
+            }
+
+            total += base as f64 * saturation * price.spike_multiplier();
+            saturation = (saturation * SATURATION_PER_UNIT).max(MIN_SATURATION);
+        }
+
+        total
+    }
+
+    /// Sells the amount and returns the whole coins earned. Fractions carry over to the next sale.
+    pub fn sell(&mut self, item: &Item, amount: i64) -> i64 {
+        self.coin_accumulator += self.quote(item, amount);
+
+        if let Some(price) = self.prices.get_mut(item) {
+            price.saturation = (price.saturation * SATURATION_PER_UNIT.powf(amount as f64)).max(MIN_SATURATION);
+        }
+
+        let coins = self.coin_accumulator.floor();
+        self.coin_accumulator -= coins;
+
+        coins as i64
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        self.tick_accumulator += dt as f64;
+
+        while self.tick_accumulator >= MARKET_TICK_SECONDS {
+            self.tick_accumulator -= MARKET_TICK_SECONDS;
+            self.tick();
+        }
+    }
+
+    fn tick(&mut self) {
+        // walk the items in a fixed order, the map order would make the rng draws unpredictable
+        for item in Item::all() {
+            let Some(price) = self.prices.get_mut(&item) else {
+                continue;
+            };
+
+            price.saturation = 1.0 - (1.0 - price.saturation) * RECOVERY_PER_TICK;
+
+            if price.spike_ticks_left > 0 {
+                price.spike_ticks_left -= 1;
+            } else if self.rng.chance(SPIKE_CHANCE_PER_TICK) {
+                price.spike_factor = self.rng.range(MIN_SPIKE_FACTOR, MAX_SPIKE_FACTOR);
+                price.spike_ticks_left = SPIKE_TICKS;
+            }
+
+            price.history.push_back(price.multiplier() * base_price(&item).unwrap_or(0) as f64);
+
+            if price.history.len() > PRICE_HISTORY_LENGTH {
+                price.history.pop_front();
+            }
+        }
+    }
+}
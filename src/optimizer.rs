@@ -0,0 +1,254 @@
+use crate::game::{GameState, Intent, Item};
+use crate::job::{JobArchetype, JobInstance, JobModifiers, JobParameters};
+use crate::job_slot::JobSlotState;
+use crate::market::base_price;
+use std::collections::HashMap;
+
+/// A recommended slot allocation and the steady-state rate it achieves.
+#[derive(Clone, PartialEq, Debug)]
+pub struct SlotPlan {
+    pub jobs: Vec<JobArchetype>,
+    pub target_per_hour: f64,
+}
+
+/// Net per-hour rate of every item once the given jobs have settled into a steady state, sped up and
+/// multiplied by each job's modifiers. Crafting jobs that don't get enough inputs only run for the fraction
+/// of time they are supplied.
+pub fn steady_state_rates(jobs: &[JobArchetype], job_modifiers: &impl Fn(&JobArchetype) -> JobModifiers) -> HashMap<Item, f64> {
+    let modifiers: Vec<JobModifiers> = jobs.iter().map(job_modifiers).collect();
+    let full_rates: Vec<f64> = jobs.iter().zip(&modifiers)
+        .map(|(job, modifiers)| 3600.0 / job.base_duration() * modifiers.speed_multiplier)
+        .collect();
+    let mut utilization = vec![1.0; jobs.len()];
+
+    // The recipe graph is acyclic, so every pass settles at least one more level of it.
+    for _ in 0..=jobs.len() {
+        let mut supply: HashMap<Item, f64> = HashMap::new();
+        let mut demand: HashMap<Item, f64> = HashMap::new();
+
+        for (i, job) in jobs.iter().enumerate() {
+            *supply.entry(job.get_product()).or_default() += utilization[i] * full_rates[i] * modifiers[i].yield_multiplier;
+
+            for (item, amount) in job.get_required_items() {
+                *demand.entry(item).or_default() += full_rates[i] * amount as f64;
+            }
+        }
+
+        for (i, job) in jobs.iter().enumerate() {
+            utilization[i] = job.get_required_items().iter()
+                .map(|(item, _)| {
+                    let supplied = supply.get(item).copied().unwrap_or(0.0);
+                    (supplied / demand[item]).min(1.0)
+                })
+                .fold(1.0, f64::min);
+        }
+    }
+
+    let mut net: HashMap<Item, f64> = HashMap::new();
+
+    for (i, job) in jobs.iter().enumerate() {
+        let rate = utilization[i] * full_rates[i];
+        *net.entry(job.get_product()).or_default() += rate * modifiers[i].yield_multiplier;
+
+        for (item, amount) in job.get_required_items() {
+            *net.entry(item).or_default() -= rate * amount as f64;
+        }
+    }
+
+    net
+}
+
+/// Jobs that make the target or anything the target is (indirectly) made from. Every job makes coins
+/// once its product is sold.
+fn relevant_jobs(target: Item, is_available: &impl Fn(&JobArchetype) -> bool) -> Vec<JobArchetype> {
+    let all_jobs: Vec<JobArchetype> = JobArchetype::all().into_iter().filter(|job| is_available(job)).collect();
+
+    if target == Item::Coin {
+        return all_jobs;
+    }
+
+    let mut wanted_items = vec![target];
+    let mut relevant: Vec<JobArchetype> = vec![];
+
+    while let Some(item) = wanted_items.pop() {
+        for job in all_jobs.iter().filter(|job| job.get_product() == item) {
+            if relevant.contains(job) {
+                continue;
+            }
+
+            relevant.push(*job);
+            wanted_items.extend(job.get_required_items().into_iter().map(|(item, _)| item));
+        }
+    }
+
+    relevant
+}
+
+/// Per-hour rate of the target in the given net rates. Coins are what the whole output sells for at base
+/// prices, minus what the consumed inputs would have sold for.
+fn target_rate(target: Item, rates: &HashMap<Item, f64>) -> f64 {
+    match target {
+        Item::Coin => rates.iter()
+            .map(|(item, rate)| rate * base_price(item).unwrap_or(0) as f64)
+            .sum(),
+        _ => rates.get(&target).copied().unwrap_or(0.0),
+    }
+}
+
+/// Steps of crafting between the job and the raw items it is made from, so sorting by it puts makers
+/// before the jobs that use up what they make.
+fn crafting_depth(job: &JobArchetype, candidates: &[JobArchetype]) -> usize {
+    job.get_required_items().iter()
+        .flat_map(|(item, _)| candidates.iter().filter(move |maker| maker.get_product() == *item))
+        .map(|maker| 1 + crafting_depth(maker, candidates))
+        .max()
+        .unwrap_or(0)
+}
+
+/// Visits every allocation of up to `slots_left` candidates that fits into `timeslots_left`. Candidates come
+/// sorted by [`crafting_depth`], so a job with an input nothing in the allocation makes never gets it from
+/// a later pick either. It would stay idle and still claim its share of its other inputs, so allocations
+/// with it are skipped.
+fn for_each_allocation(candidates: &[JobArchetype], slots_left: usize, timeslots_left: i32, current: &mut Vec<JobArchetype>, visit: &mut impl FnMut(&[JobArchetype])) {
+    visit(current);
+
+    if slots_left == 0 {
+        return;
+    }
+
+    for (i, job) in candidates.iter().enumerate() {
+        let is_supplied = job.get_required_items().iter()
+            .all(|(item, _)| current.iter().any(|maker| maker.get_product() == *item));
+
+        if job.timeslot_cost() > timeslots_left || !is_supplied {
+            continue;
+        }
+
+        current.push(*job);
+        // only pass on candidates from i onwards, so every multiset is visited once
+        for_each_allocation(&candidates[i..], slots_left - 1, timeslots_left - job.timeslot_cost(), current, visit);
+        current.pop();
+    }
+}
+
+/// Finds the allocation of up to `slot_count` slots that maximizes the net rate of `target` at base speed.
+/// Among equally good allocations the one using the fewest slots wins. For coins, the plan sells everything
+/// it makes at base prices.
+pub fn optimize_slots(target: Item, slot_count: usize) -> SlotPlan {
+    optimize_slots_for(target, slot_count, i32::MAX, |_| true, |_| JobModifiers::none())
+}
+
+/// Like [`optimize_slots`], but only plans with the jobs the player has access to, that fit into the
+/// time slot capacity together, at the speed and yield the jobs currently have. Modifiers of a particular
+/// slot, like runes and workers, are left out since the plan doesn't say which slot a job goes into.
+pub fn optimize_slots_for(target: Item, slot_count: usize, timeslot_capacity: i32, is_available: impl Fn(&JobArchetype) -> bool, job_modifiers: impl Fn(&JobArchetype) -> JobModifiers) -> SlotPlan {
+    let relevant = relevant_jobs(target, &is_available);
+    let mut candidates = relevant.clone();
+    candidates.sort_by_cached_key(|job| crafting_depth(job, &relevant));
+    let modifiers: HashMap<JobArchetype, JobModifiers> = candidates.iter().map(|job| (*job, job_modifiers(job))).collect();
+
+    let mut best = SlotPlan { jobs: vec![], target_per_hour: 0.0 };
+
+    for_each_allocation(&candidates, slot_count, timeslot_capacity, &mut vec![], &mut |jobs| {
+        let target_per_hour = target_rate(target, &steady_state_rates(jobs, &|job| modifiers[job]));
+
+        let is_better = target_per_hour > best.target_per_hour + 1e-9
+            || ((target_per_hour - best.target_per_hour).abs() <= 1e-9 && jobs.len() < best.jobs.len());
+
+        if is_better {
+            best = SlotPlan { jobs: jobs.to_vec(), target_per_hour };
+        }
+    });
+
+    best
+}
+
+/// Runs the plan for the target on the unlocked slots. Slots that already run a planned job keep it, the
+/// rest of the plan goes into open slots and in place of the cheapest other jobs. Jobs it doesn't need a
+/// slot for are left alone.
+pub fn apply_plan(state: &mut GameState, target: Item) {
+    let plan = |state: &GameState, slot_count: usize, capacity: i32| optimize_slots_for(
+        target,
+        slot_count,
+        capacity,
+        |job| state.is_job_available(job),
+        |job| state.job_modifiers(&JobInstance::new(JobParameters { job_archetype: *job })),
+    ).jobs;
+
+    let unlocked_slots: Vec<usize> = state.job_slots.iter()
+        .filter(|slot| !matches!(slot.state, JobSlotState::Locked))
+        .map(|slot| slot.index)
+        .collect();
+
+    // a plan for the whole board decides which other jobs are in the way
+    let mut unplaced_jobs = plan(state, unlocked_slots.len(), state.timeslot_capacity());
+    let mut open_slots = 0;
+    let mut other_jobs = vec![];
+
+    for index in &unlocked_slots {
+        match &state.job_slots[*index].state {
+            JobSlotState::RunningJob(job_instance) => {
+                match unplaced_jobs.iter().position(|job| *job == job_instance.job_archetype) {
+                    Some(position) => { unplaced_jobs.remove(position); }
+                    None => other_jobs.push((*index, job_instance.job_archetype.get_product())),
+                }
+            }
+            _ => open_slots += 1,
+        }
+    }
+
+    // the plan takes open slots first, then replaces the cheapest other jobs until it has enough slots
+    // and time slots, and leaves the rest alone
+    other_jobs.sort_by_key(|(_, product)| base_price(product).unwrap_or(0));
+    let slot_timeslots = |state: &GameState, index: usize| match &state.job_slots[index].state {
+        JobSlotState::RunningJob(job_instance) if job_instance.running => job_instance.timeslot_cost,
+        _ => 0,
+    };
+    let needed_timeslots: i32 = unplaced_jobs.iter().map(|job| job.timeslot_cost()).sum();
+    let mut free_timeslots = state.timeslot_capacity() - state.timeslots_used(None);
+    let mut replaced_count = 0;
+
+    while replaced_count < other_jobs.len() && (open_slots + replaced_count < unplaced_jobs.len() || free_timeslots < needed_timeslots) {
+        free_timeslots += slot_timeslots(state, other_jobs[replaced_count].0);
+        replaced_count += 1;
+    }
+
+    let left_alone: Vec<usize> = other_jobs[replaced_count..].iter().map(|(index, _)| *index).collect();
+
+    // jobs left alone keep their time slots, so the actual plan only gets what is free without them
+    let left_alone_timeslots: i32 = left_alone.iter().map(|index| slot_timeslots(state, *index)).sum();
+    let mut planned_jobs = plan(
+        state,
+        unlocked_slots.len() - left_alone.len(),
+        state.timeslot_capacity() - left_alone_timeslots,
+    );
+    let mut free_slots = vec![];
+
+    // slots that already run a planned job keep their progress, the others are cleared before placing anything
+    for index in unlocked_slots.into_iter().filter(|index| !left_alone.contains(index)) {
+        let kept = match &state.job_slots[index].state {
+            JobSlotState::RunningJob(job_instance) => planned_jobs.iter().position(|job| *job == job_instance.job_archetype),
+            _ => None,
+        };
+
+        match kept {
+            Some(position) => { planned_jobs.remove(position); }
+            None => {
+                if matches!(state.job_slots[index].state, JobSlotState::RunningJob(_)) {
+                    state.job_slots[index].refund_paid_inputs(&mut state.inventory);
+                    state.job_slots[index].set_state(JobSlotState::Empty);
+                }
+
+                free_slots.push(index);
+            }
+        }
+    }
+
+    // the heaviest jobs go first, while the most time slots are free
+    planned_jobs.sort_by_key(|job| std::cmp::Reverse(job.timeslot_cost()));
+
+    for (index, job_archetype) in free_slots.into_iter().zip(planned_jobs) {
+        let new_state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype }));
+        Intent::ChangeJobSlotState(index, new_state).execute(state);
+    }
+}
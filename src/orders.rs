@@ -0,0 +1,287 @@
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{button, number_pill, BorderStyle, UiElement};
+use crate::equipment::Tool;
+use crate::game::{pretty_duration, GameState, Intent, Item, UiRect};
+use crate::job::JobArchetype;
+use crate::market::base_price;
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::rng::Rng;
+use crate::skill::{SkillArchetype, SkillArchetypeInstances};
+use strum::IntoEnumIterator;
+
+pub const MAX_ORDERS: usize = 4;
+pub const ORDER_REFRESH_SECONDS: f64 = 5.0 * 60.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum OrderReward {
+    Coins(i64),
+    SkillActions(SkillArchetype, i64),
+    Item(Item, i64),
+}
+
+impl OrderReward {
+    pub fn describe(&self) -> String {
+        match self {
+            OrderReward::Coins(coins) => format!("{} Coins", coins),
+            OrderReward::SkillActions(skill, actions) => format!("{} {} XP", actions, skill.get_name()),
+            OrderReward::Item(item, amount) => format!("{} {}", amount, item.get_name()),
+        }
+    }
+}
+
+/// A villager's request for a bundle of items, open until the deadline runs out.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Order {
+    pub requests: Vec<(Item, i64)>,
+    pub reward: OrderReward,
+    pub remaining_seconds: f64,
+}
+
+impl Order {
+    pub fn can_fulfill(&self, state: &GameState) -> bool {
+        self.requests.iter().all(|(item, amount)| state.inventory.get_item_amount(item) >= *amount)
+    }
+}
+
+pub struct OrderBoard {
+    pub orders: Vec<Order>,
+    pub refresh_timer: f64,
+}
+
+impl OrderBoard {
+    pub fn new() -> Self {
+        Self {
+            orders: vec![],
+            refresh_timer: 0.0,
+        }
+    }
+
+    /// Counts down the deadlines. Returns true every few minutes while there is room for a new order.
+    pub fn update(&mut self, dt: f32) -> bool {
+        for order in &mut self.orders {
+            order.remaining_seconds -= dt as f64;
+        }
+
+        self.orders.retain(|order| order.remaining_seconds > 0.0);
+
+        self.refresh_timer -= dt as f64;
+
+        if self.refresh_timer <= 0.0 {
+            self.refresh_timer += ORDER_REFRESH_SECONDS;
+            return self.orders.len() < MAX_ORDERS;
+        }
+
+        false
+    }
+}
+
+/// Steps from raw materials to the item in the recipe graph: gathered items are 0, crafted ones
+/// are one more than their deepest input.
+pub fn recipe_depth(item: &Item) -> i64 {
+    JobArchetype::all().iter()
+        .filter(|job| job.get_product() == *item)
+        .map(|job| {
+            job.get_required_items().iter()
+                .map(|(input, _)| 1 + recipe_depth(input))
+                .max()
+                .unwrap_or(0)
+        })
+        .min()
+        .unwrap_or(0)
+}
+
+/// Products of the given jobs that villagers ask for. Tools are only ever handed out as rewards.
+fn orderable_items(available_jobs: &[JobArchetype]) -> Vec<Item> {
+    let mut items: Vec<Item> = vec![];
+
+    for job in available_jobs {
+        let product = job.get_product();
+
+        if !matches!(product, Item::Tool(_)) && !items.contains(&product) {
+            items.push(product);
+        }
+    }
+
+    items
+}
+
+fn producing_skill(item: &Item) -> SkillArchetype {
+    JobArchetype::all().iter()
+        .find(|job| job.get_product() == *item)
+        .map(|job| job.get_skill_type())
+        .unwrap_or(SkillArchetype::Lumbering)
+}
+
+/// Picks one or two items the available jobs make and asks for more of them the better the player
+/// is at making them. Deeper recipes are asked for in smaller amounts.
+pub fn generate_order(rng: &mut Rng, skills: &SkillArchetypeInstances, available_jobs: &[JobArchetype]) -> Order {
+    let candidates = orderable_items(available_jobs);
+    let item_count = if rng.chance(0.4) { 2 } else { 1 }.min(candidates.len());
+    let mut requests: Vec<(Item, i64)> = vec![];
+
+    while requests.len() < item_count {
+        let item = candidates[rng.index(candidates.len())];
+
+        if requests.iter().any(|(requested, _)| *requested == item) {
+            continue;
+        }
+
+        let level = skills.get_skill_by_type(&producing_skill(&item)).actions_counter.level;
+        let amount = ((4.0 + level as f64 * 1.5) / (recipe_depth(&item) + 1) as f64).ceil() as i64;
+
+        requests.push((item, amount));
+    }
+
+    let value: i64 = requests.iter()
+        .map(|(item, amount)| base_price(item).unwrap_or(1) * amount)
+        .sum();
+
+    let roll = rng.next_f64();
+
+    let reward = if roll < 0.6 {
+        OrderReward::Coins(value * 2)
+    } else if roll < 0.85 {
+        let total_amount: i64 = requests.iter().map(|(_, amount)| amount).sum();
+        OrderReward::SkillActions(producing_skill(&requests[0].0), total_amount * 2)
+    } else {
+        let tools: Vec<Tool> = Tool::iter().collect();
+        OrderReward::Item(Item::Tool(tools[rng.index(tools.len())]), 1)
+    };
+
+    Order {
+        remaining_seconds: 30.0 * 60.0 * item_count as f64,
+        requests,
+        reward,
+    }
+}
+
+pub fn fulfill_order(state: &mut GameState, index: usize) -> bool {
+    let Some(order) = state.order_board.orders.get(index) else {
+        return false;
+    };
+
+    if !order.can_fulfill(state) {
+        return false;
+    }
+
+    let order = state.order_board.orders.remove(index);
+
+    for (item, amount) in &order.requests {
+        state.inventory.add_item(*item, -amount);
+    }
+
+    match order.reward {
+        OrderReward::Coins(coins) => state.inventory.add_item(Item::Coin, coins),
+        OrderReward::Item(item, amount) => state.inventory.add_item(item, amount),
+        OrderReward::SkillActions(skill, actions) => {
+            let skill_instance = state.skill_archetype_instances.get_skill_by_type_mut(&skill);
+
+            for _ in 0..actions {
+                skill_instance.increment_actions();
+            }
+        }
+    }
+
+    true
+}
+
+pub fn build_orders_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let spacing = 16.0;
+    let card_width = (rect.w - padding * 2.0 - spacing * (MAX_ORDERS as f32 - 1.0)) / MAX_ORDERS as f32;
+    let card_height = 320.0;
+    let icon_size = 48.0;
+
+    elements.push(UiElement::Text {
+        content: format!("Orders Board - next order in {}", pretty_duration(state.order_board.refresh_timer)),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    for (i, order) in state.order_board.orders.iter().enumerate() {
+        let x = rect.x + padding + i as f32 * (card_width + spacing);
+        let y = rect.y + padding + 50.0;
+
+        elements.push(UiElement::Rectangle {
+            x,
+            y,
+            width: card_width,
+            height: card_height,
+            color: palette::IMAGE_BACKGROUND.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::Text {
+            content: format!("Due in {}", pretty_duration(order.remaining_seconds)),
+            font: assets.fonts.text_bold.clone(),
+            x: x + 12.0,
+            y: y + 24.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        for (j, (item, amount)) in order.requests.iter().enumerate() {
+            let item_y = y + 44.0 + j as f32 * (icon_size + 20.0);
+            let have = state.inventory.get_item_amount(item);
+
+            elements.push(UiElement::Image {
+                x: x + 12.0,
+                y: item_y,
+                width: icon_size,
+                height: icon_size,
+                texture: item.get_texture(assets),
+                color: PaletteC::White.get_color(),
+            });
+
+            elements.push(UiElement::Text {
+                content: format!("{} {}", amount, item.get_name()),
+                font: assets.fonts.text.clone(),
+                x: x + 20.0 + icon_size,
+                y: item_y + icon_size / 2.0 + 6.0,
+                font_size: 16.0,
+                color: palette::TEXT.get_color(),
+            });
+
+            elements.extend(number_pill(
+                x + 12.0 + icon_size / 2.0 - 16.0,
+                item_y + icon_size - 4.0,
+                32.0,
+                14.0,
+                have,
+                if have >= *amount { Some(PaletteC::Peach.get_color()) } else { Some(PaletteC::Coral.get_color()) },
+                assets.fonts.mono.clone(),
+            ));
+        }
+
+        elements.push(UiElement::Text {
+            content: format!("Reward: {}", order.reward.describe()),
+            font: assets.fonts.text_bold.clone(),
+            x: x + 12.0,
+            y: y + card_height - 64.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(button(assets, UiRect::new(x + 12.0, y + card_height - 44.0, card_width - 24.0 - 40.0, 32.0),
+            "Deliver", order.can_fulfill(state), Intent::FulfillOrder(i), None));
+        elements.push(button(assets, UiRect::new(x + card_width - 12.0 - 32.0, y + card_height - 44.0, 32.0, 32.0),
+            "x", true, Intent::DiscardOrder(i), None));
+    }
+
+    elements
+}
@@ -0,0 +1,220 @@
+use crate::achievements::Achievements;
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::automation::Automation;
+use crate::draw::{button, UiElement};
+use crate::game::{GameState, Intent, UiRect, DEFAULT_MARKET_SEED};
+use crate::job::JobModifiers;
+use crate::job_slot::JobSlotState;
+use crate::market::Market;
+use crate::palette;
+use std::collections::HashMap;
+use std::mem;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Lifetime skill actions needed for the first point of renown. Every further point needs more.
+pub const ACTIONS_PER_RENOWN: f64 = 50.0;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum PrestigePerk {
+    Swiftness,
+    Abundance,
+    Momentum,
+}
+
+impl PrestigePerk {
+    pub fn get_name(&self) -> &str {
+        match self {
+            PrestigePerk::Swiftness => "Swiftness",
+            PrestigePerk::Abundance => "Abundance",
+            PrestigePerk::Momentum => "Momentum",
+        }
+    }
+
+    pub fn get_description(&self) -> &str {
+        match self {
+            PrestigePerk::Swiftness => "+10% speed for all jobs per level",
+            PrestigePerk::Abundance => "+10% yield for all jobs per level",
+            PrestigePerk::Momentum => "+20% hyper mode strength per level",
+        }
+    }
+
+    /// Renown for the next level.
+    pub fn cost(&self, level: i64) -> i64 {
+        let base_cost = match self {
+            PrestigePerk::Swiftness => 1,
+            PrestigePerk::Abundance => 2,
+            PrestigePerk::Momentum => 3,
+        };
+
+        base_cost * (level + 1)
+    }
+
+    pub fn get_modifiers(&self, level: i64) -> JobModifiers {
+        let bonus = level as f64;
+
+        match self {
+            PrestigePerk::Swiftness => JobModifiers { speed_multiplier: 1.0 + 0.1 * bonus, ..JobModifiers::none() },
+            PrestigePerk::Abundance => JobModifiers { yield_multiplier: 1.0 + 0.1 * bonus, ..JobModifiers::none() },
+            PrestigePerk::Momentum => JobModifiers { hyper_multiplier: 1.0 + 0.2 * bonus, ..JobModifiers::none() },
+        }
+    }
+}
+
+/// Everything that survives a prestige reset. Saved apart from the run itself.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Prestige {
+    pub renown: i64,
+    pub resets: i64,
+    pub perk_levels: HashMap<PrestigePerk, i64>,
+    /// Skill actions of all earlier runs, renown is paid out for what the current run adds to them.
+    pub lifetime_actions: i64,
+}
+
+impl Prestige {
+    pub fn new() -> Self {
+        Self {
+            renown: 0,
+            resets: 0,
+            perk_levels: HashMap::new(),
+            lifetime_actions: 0,
+        }
+    }
+
+    pub fn get_level(&self, perk: &PrestigePerk) -> i64 {
+        *self.perk_levels.get(perk).unwrap_or(&0)
+    }
+
+    pub fn get_modifiers(&self) -> JobModifiers {
+        PrestigePerk::iter()
+            .map(|perk| perk.get_modifiers(self.get_level(&perk)))
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+
+    pub fn buy_perk(&mut self, perk: PrestigePerk) -> bool {
+        let level = self.get_level(&perk);
+        let cost = perk.cost(level);
+
+        if self.renown < cost {
+            return false;
+        }
+
+        self.renown -= cost;
+        self.perk_levels.insert(perk, level + 1);
+
+        true
+    }
+}
+
+fn run_actions(state: &GameState) -> i64 {
+    state.skill_archetype_instances.instances.iter()
+        .map(|instance| instance.actions_counter.actions_done_total)
+        .sum()
+}
+
+fn renown_for_actions(actions: i64) -> i64 {
+    (actions as f64 / ACTIONS_PER_RENOWN).sqrt().floor() as i64
+}
+
+/// Renown a reset would give right now: what the skill actions of this run add to the lifetime total.
+pub fn renown_for_reset(state: &GameState) -> i64 {
+    let lifetime_actions = state.prestige.lifetime_actions;
+
+    renown_for_actions(lifetime_actions + run_actions(state)) - renown_for_actions(lifetime_actions)
+}
+
+/// Starts a new run. Inventory, levels, slots and everything bought with coins are gone,
+/// renown, achievements and automation rules stay.
+pub fn prestige_reset(state: &mut GameState) -> bool {
+    let renown = renown_for_reset(state);
+
+    if renown < 1 {
+        return false;
+    }
+
+    let mut fresh = GameState::new();
+
+    fresh.prestige = mem::replace(&mut state.prestige, Prestige::new());
+    fresh.prestige.renown += renown;
+    fresh.prestige.lifetime_actions += run_actions(state);
+    fresh.prestige.resets += 1;
+    fresh.achievements = mem::replace(&mut state.achievements, Achievements::new());
+    fresh.automation = mem::replace(&mut state.automation, Automation::new());
+    fresh.market = mem::replace(&mut state.market, Market::new(DEFAULT_MARKET_SEED));
+    fresh.rng = state.rng.clone();
+    fresh.game_tab = state.game_tab.clone();
+    fresh.job_slots[0].set_state(JobSlotState::Empty);
+
+    *state = fresh;
+
+    true
+}
+
+pub fn build_prestige_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let row_height = 56.0;
+    let prestige = &state.prestige;
+    let renown_gain = renown_for_reset(state);
+
+    elements.push(UiElement::Text {
+        content: format!("Renown: {}   Resets: {}", prestige.renown, prestige.resets),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    elements.push(UiElement::Text {
+        content: "Starting over clears your inventory, levels, slots, research, buildings, land, workers, friendships and shop upgrades.".to_string(),
+        font: assets.fonts.text.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 56.0,
+        font_size: 16.0,
+        color: palette::BORDER.get_color(),
+    });
+
+    elements.push(button(assets, UiRect::new(rect.x + rect.w - padding - 240.0, rect.y + padding, 240.0, 40.0),
+        &format!("Start over for {} renown", renown_gain), renown_gain > 0, Intent::Prestige, None));
+
+    for (i, perk) in PrestigePerk::iter().enumerate() {
+        let x = rect.x + padding;
+        let y = rect.y + padding + 100.0 + i as f32 * row_height;
+        let level = prestige.get_level(&perk);
+        let cost = perk.cost(level);
+
+        elements.push(UiElement::Text {
+            content: format!("{} Lv. {}", perk.get_name(), level),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 18.0,
+            font_size: 18.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::Text {
+            content: perk.get_description().to_string(),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 38.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        elements.push(button(assets, UiRect::new(x + 400.0, y + 4.0, 140.0, 32.0),
+            &format!("{} renown", cost), prestige.renown >= cost, Intent::BuyPrestigePerk(perk), None));
+    }
+
+    elements
+}
@@ -0,0 +1,244 @@
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{pretty_duration, GameState, Intent, Inventory, Item, UiRect};
+use crate::job::{JobArchetype, FUEL_ITEM};
+use crate::job_slot::JobSlotState;
+use crate::palette;
+use crate::palette::PaletteC;
+use std::collections::HashMap;
+
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct ItemFlow {
+    pub produced_per_hour: f64,
+    pub consumed_per_hour: f64,
+}
+
+impl ItemFlow {
+    pub fn net_per_hour(&self) -> f64 {
+        self.produced_per_hour - self.consumed_per_hour
+    }
+}
+
+/// Per-item production and consumption of all running job slots.
+pub struct ProductionReport {
+    pub flows: HashMap<Item, ItemFlow>,
+}
+
+impl ProductionReport {
+    pub fn from_state(state: &GameState) -> Self {
+        let mut flows: HashMap<Item, ItemFlow> = HashMap::new();
+
+        for slot in &state.job_slots {
+            let JobSlotState::RunningJob(job_instance) = &slot.state else {
+                continue;
+            };
+
+            // stalled jobs and jobs waiting for inputs make nothing
+            if !state.is_slot_active(slot) || !job_instance.can_start(&state.inventory) {
+                continue;
+            }
+
+            let modifiers = state.slot_modifiers(slot);
+            let actions_per_hour = job_instance.actions_per_hour(&modifiers);
+            let archetype = job_instance.job_archetype;
+
+            flows.entry(archetype.get_product()).or_default()
+                .produced_per_hour += actions_per_hour * modifiers.yield_multiplier;
+
+            for (item, amount) in archetype.get_required_items() {
+                flows.entry(item).or_default()
+                    .consumed_per_hour += actions_per_hour * amount as f64;
+            }
+
+            if archetype.uses_fuel() && state.inventory.get_item_amount(&FUEL_ITEM) > 0 {
+                flows.entry(FUEL_ITEM).or_default()
+                    .consumed_per_hour += actions_per_hour;
+            }
+        }
+
+        Self { flows }
+    }
+
+    pub fn get_flow(&self, item: &Item) -> ItemFlow {
+        self.flows.get(item).copied().unwrap_or_default()
+    }
+
+    /// Seconds until the item is used up at the current rates,
+    /// or None if the item is not being drained.
+    pub fn seconds_until_empty(&self, item: &Item, inventory: &Inventory) -> Option<f64> {
+        let net_per_hour = self.get_flow(item).net_per_hour();
+
+        if net_per_hour >= 0.0 {
+            return None;
+        }
+
+        let amount = inventory.get_item_amount(item).max(0) as f64;
+
+        Some(amount / -net_per_hour * 3600.0)
+    }
+
+    /// All flows, sorted by item name so the overlay doesn't jump around.
+    pub fn sorted_flows(&self) -> Vec<(Item, ItemFlow)> {
+        let mut flows: Vec<(Item, ItemFlow)> = self.flows.iter().map(|(item, flow)| (*item, *flow)).collect();
+        flows.sort_by_key(|(item, _)| item.get_name());
+        flows
+    }
+}
+
+fn format_rate(rate: f64) -> String {
+    if rate.abs() < 0.05 {
+        "0".to_string()
+    } else {
+        format!("{:+.1}", rate)
+    }
+}
+
+pub fn build_rates_overlay(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let row_height = 36.0;
+    let icon_size = 28.0;
+    let font_size = 16.0;
+    let columns = [0.0, 220.0, 340.0, 460.0, 580.0];
+
+    elements.push(UiElement::Text {
+        content: "Production per hour".to_string(),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    let header_y = rect.y + padding + 60.0;
+    for (column, title) in columns.iter().zip(["Item", "Produced", "Consumed", "Net", "Runs dry in"]) {
+        elements.push(UiElement::Text {
+            content: title.to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x: rect.x + padding + column,
+            y: header_y,
+            font_size,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    let report = ProductionReport::from_state(state);
+    let flows = report.sorted_flows();
+
+    if flows.is_empty() {
+        elements.push(UiElement::Text {
+            content: "No jobs are running.".to_string(),
+            font: assets.fonts.text.clone(),
+            x: rect.x + padding,
+            y: header_y + row_height,
+            font_size,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    for (i, (item, flow)) in flows.iter().enumerate() {
+        let row_y = header_y + 12.0 + i as f32 * row_height;
+        let text_y = row_y + icon_size / 2.0 + font_size / 3.0;
+        let eta = report.seconds_until_empty(item, &state.inventory);
+
+        elements.push(UiElement::Rectangle {
+            x: rect.x + padding,
+            y: row_y,
+            width: icon_size,
+            height: icon_size,
+            color: if eta.is_some() { PaletteC::Coral.get_color() } else { palette::IMAGE_BACKGROUND.get_color() },
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::Image {
+            x: rect.x + padding + 2.0,
+            y: row_y + 2.0,
+            width: icon_size - 4.0,
+            height: icon_size - 4.0,
+            texture: item.get_texture(assets),
+            color: PaletteC::White.get_color(),
+        });
+
+        let cells = [
+            (columns[0] + icon_size + 8.0, item.get_name()),
+            (columns[1], format_rate(flow.produced_per_hour)),
+            (columns[2], format_rate(-flow.consumed_per_hour)),
+            (columns[3], format_rate(flow.net_per_hour())),
+            (columns[4], eta.map(pretty_duration).unwrap_or_else(|| "-".to_string())),
+        ];
+
+        for (column, content) in cells {
+            elements.push(UiElement::Text {
+                content,
+                font: assets.fonts.mono.clone(),
+                x: rect.x + padding + column,
+                y: text_y,
+                font_size,
+                color: if eta.is_some() { PaletteC::Coral.get_color() } else { palette::TEXT.get_color() },
+            });
+        }
+    }
+
+    elements.extend(optimize_buttons_ui(assets, rect));
+
+    elements
+}
+
+fn optimize_buttons_ui(assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    let padding = 24.0;
+    let button_size = 40.0;
+    let button_spacing = 8.0;
+    let row_y = rect.y + rect.h - padding - button_size;
+
+    elements.push(UiElement::Text {
+        content: "Optimize slots for:".to_string(),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: row_y - 10.0,
+        font_size: 16.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    // coins come from selling whatever the plan makes
+    let mut products: Vec<Item> = vec![Item::Coin];
+    for job in JobArchetype::all() {
+        if !products.contains(&job.get_product()) {
+            products.push(job.get_product());
+        }
+    }
+
+    for (i, product) in products.iter().enumerate() {
+        let button_x = rect.x + padding + i as f32 * (button_size + button_spacing);
+
+        elements.push(UiElement::Rectangle {
+            x: button_x,
+            y: row_y,
+            width: button_size,
+            height: button_size,
+            color: palette::IMAGE_BACKGROUND.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::ImgButton {
+            rectangle: UiRect::new(button_x, row_y, button_size, button_size),
+            intent: Intent::OptimizeSlots(*product),
+            texture: product.get_texture(assets),
+            parent_clip: None,
+            border_style: BorderStyle::None,
+        });
+    }
+
+    elements
+}
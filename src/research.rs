@@ -0,0 +1,452 @@
+use crate::toasts::{Toast, TOAST_SECONDS};
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::automation::MAX_RULES;
+use crate::draw::{button, BorderStyle, UiElement};
+use crate::game::{pretty_duration, GameState, Intent, Inventory, Item, UiRect, WoodItem, WoodWorkingItem};
+use crate::job::{AlchemyJobArchetype, JobArchetype, JobModifiers, SmithingJobArchetype, WoodworkingJobArchetype};
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::skill::SkillArchetype;
+use macroquad::color::Color;
+use macroquad::math::Vec2;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Slots that can be unlocked and automation rules that can be set up before any research.
+pub const BASE_JOB_SLOTS: usize = 4;
+pub const BASE_RULES: usize = 3;
+
+/// How far one click on the pan buttons moves the graph.
+pub const PAN_STEP: f32 = 120.0;
+
+const NODE_WIDTH: f32 = 200.0;
+const NODE_HEIGHT: f32 = 84.0;
+const COLUMN_WIDTH: f32 = 260.0;
+const ROW_HEIGHT: f32 = 100.0;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum ResearchNode {
+    Carpentry,
+    Smelting,
+    Cookery,
+    Herbalism,
+    Joinery,
+    Expansion,
+    Toolmaking,
+    Attunement,
+    Settlement,
+    Clockwork,
+    Resonance,
+    Runecraft,
+}
+
+/// What finishing a research node makes available.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ResearchUnlock {
+    Skill(SkillArchetype),
+    Recipes(Vec<JobArchetype>),
+    JobSlots(usize),
+    Rules(usize),
+    HyperBoost(f64),
+}
+
+impl ResearchNode {
+    pub fn get_name(&self) -> &str {
+        match self {
+            ResearchNode::Carpentry => "Carpentry",
+            ResearchNode::Smelting => "Smelting",
+            ResearchNode::Cookery => "Cookery",
+            ResearchNode::Herbalism => "Herbalism",
+            ResearchNode::Joinery => "Joinery",
+            ResearchNode::Expansion => "Expansion",
+            ResearchNode::Toolmaking => "Toolmaking",
+            ResearchNode::Attunement => "Attunement",
+            ResearchNode::Settlement => "Settlement",
+            ResearchNode::Clockwork => "Clockwork",
+            ResearchNode::Resonance => "Resonance",
+            ResearchNode::Runecraft => "Runecraft",
+        }
+    }
+
+    pub fn get_unlock(&self) -> ResearchUnlock {
+        match self {
+            ResearchNode::Carpentry => ResearchUnlock::Skill(SkillArchetype::Woodworking),
+            ResearchNode::Smelting => ResearchUnlock::Skill(SkillArchetype::Smithing),
+            ResearchNode::Cookery => ResearchUnlock::Skill(SkillArchetype::Cooking),
+            ResearchNode::Herbalism => ResearchUnlock::Skill(SkillArchetype::Alchemy),
+            ResearchNode::Joinery => ResearchUnlock::Recipes(vec![
+                JobArchetype::Woodworking(WoodworkingJobArchetype::GrainPlank),
+                JobArchetype::Woodworking(WoodworkingJobArchetype::Beam),
+                JobArchetype::Woodworking(WoodworkingJobArchetype::Crate),
+            ]),
+            ResearchNode::Expansion => ResearchUnlock::JobSlots(5),
+            ResearchNode::Toolmaking => ResearchUnlock::Recipes(vec![
+                JobArchetype::Smithing(SmithingJobArchetype::Axe),
+                JobArchetype::Smithing(SmithingJobArchetype::Pickaxe),
+                JobArchetype::Smithing(SmithingJobArchetype::Knife),
+                JobArchetype::Smithing(SmithingJobArchetype::Mortar),
+            ]),
+            ResearchNode::Attunement => ResearchUnlock::HyperBoost(1.25),
+            ResearchNode::Settlement => ResearchUnlock::JobSlots(6),
+            ResearchNode::Clockwork => ResearchUnlock::Rules(MAX_RULES),
+            ResearchNode::Resonance => ResearchUnlock::HyperBoost(1.25),
+            ResearchNode::Runecraft => ResearchUnlock::Recipes(vec![
+                JobArchetype::Alchemy(AlchemyJobArchetype::BlankRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::TimeRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::HeartRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::GreaterTimeRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::GreaterHeartRune),
+            ]),
+        }
+    }
+
+    pub fn get_description(&self) -> String {
+        match self.get_unlock() {
+            ResearchUnlock::Skill(skill) => format!("Unlocks {}", skill.get_name()),
+            ResearchUnlock::Recipes(jobs) => format!("Unlocks {}", jobs.iter().map(|job| job.get_name()).collect::<Vec<_>>().join(", ")),
+            ResearchUnlock::JobSlots(slots) => format!("Up to {} job slots", slots),
+            ResearchUnlock::Rules(rules) => format!("Up to {} automation rules", rules),
+            ResearchUnlock::HyperBoost(multiplier) => format!("Hyper mode {:.0}% stronger", (multiplier - 1.0) * 100.0),
+        }
+    }
+
+    pub fn get_prerequisites(&self) -> Vec<ResearchNode> {
+        match self {
+            ResearchNode::Carpentry | ResearchNode::Smelting | ResearchNode::Cookery | ResearchNode::Herbalism => vec![],
+            ResearchNode::Joinery | ResearchNode::Expansion => vec![ResearchNode::Carpentry],
+            ResearchNode::Toolmaking => vec![ResearchNode::Smelting],
+            ResearchNode::Attunement => vec![ResearchNode::Herbalism],
+            ResearchNode::Settlement => vec![ResearchNode::Expansion, ResearchNode::Joinery],
+            ResearchNode::Clockwork => vec![ResearchNode::Toolmaking],
+            ResearchNode::Resonance => vec![ResearchNode::Attunement, ResearchNode::Clockwork],
+            ResearchNode::Runecraft => vec![ResearchNode::Herbalism, ResearchNode::Smelting],
+        }
+    }
+
+    /// Paid in full when the research starts.
+    pub fn get_cost(&self) -> Vec<(Item, i64)> {
+        match self {
+            ResearchNode::Carpentry => vec![(Item::Wood(WoodItem::Craftwood), 40)],
+            ResearchNode::Smelting => vec![(Item::IronOre, 30)],
+            ResearchNode::Cookery => vec![(Item::Meat, 20)],
+            ResearchNode::Herbalism => vec![(Item::Herb, 30)],
+            ResearchNode::Joinery => vec![(Item::Woodworking(WoodWorkingItem::Plank), 25)],
+            ResearchNode::Expansion => vec![(Item::Woodworking(WoodWorkingItem::Plank), 40), (Item::Coin, 250)],
+            ResearchNode::Toolmaking => vec![(Item::IronBar, 20), (Item::Coin, 100)],
+            ResearchNode::Attunement => vec![(Item::ManaPotion, 10)],
+            ResearchNode::Settlement => vec![(Item::Woodworking(WoodWorkingItem::Beam), 20), (Item::Coin, 1000)],
+            ResearchNode::Clockwork => vec![(Item::IronBar, 15), (Item::Woodworking(WoodWorkingItem::Plank), 10)],
+            ResearchNode::Resonance => vec![(Item::ManaPotion, 25), (Item::Woodworking(WoodWorkingItem::Crate), 5)],
+            ResearchNode::Runecraft => vec![(Item::ManaPotion, 10), (Item::IronBar, 10)],
+        }
+    }
+
+    pub fn get_duration(&self) -> f64 {
+        match self {
+            ResearchNode::Carpentry | ResearchNode::Cookery => 60.0,
+            ResearchNode::Smelting | ResearchNode::Herbalism => 90.0,
+            ResearchNode::Joinery => 5.0 * 60.0,
+            ResearchNode::Expansion | ResearchNode::Toolmaking | ResearchNode::Attunement | ResearchNode::Runecraft => 10.0 * 60.0,
+            ResearchNode::Clockwork => 15.0 * 60.0,
+            ResearchNode::Settlement | ResearchNode::Resonance => 30.0 * 60.0,
+        }
+    }
+
+    /// Column and row of the node in the research graph.
+    pub fn get_position(&self) -> (usize, usize) {
+        match self {
+            ResearchNode::Carpentry => (0, 0),
+            ResearchNode::Smelting => (0, 2),
+            ResearchNode::Cookery => (0, 3),
+            ResearchNode::Herbalism => (0, 4),
+            ResearchNode::Joinery => (1, 0),
+            ResearchNode::Expansion => (1, 1),
+            ResearchNode::Toolmaking => (1, 2),
+            ResearchNode::Attunement => (1, 4),
+            ResearchNode::Settlement => (2, 1),
+            ResearchNode::Clockwork => (2, 2),
+            ResearchNode::Resonance => (3, 3),
+            ResearchNode::Runecraft => (1, 3),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ActiveResearch {
+    pub node: ResearchNode,
+    pub elapsed_seconds: f64,
+}
+
+impl ActiveResearch {
+    pub fn progress(&self) -> f64 {
+        (self.elapsed_seconds / self.node.get_duration()).min(1.0)
+    }
+}
+
+/// Finished research nodes and the one being worked on. Only one node is researched at a time.
+pub struct Research {
+    pub completed: Vec<ResearchNode>,
+    pub active: Option<ActiveResearch>,
+    pub pan: Vec2,
+}
+
+impl Research {
+    pub fn new() -> Self {
+        Self {
+            completed: vec![],
+            active: None,
+            pan: Vec2::ZERO,
+        }
+    }
+
+    pub fn is_completed(&self, node: &ResearchNode) -> bool {
+        self.completed.contains(node)
+    }
+
+    pub fn can_start(&self, node: &ResearchNode, inventory: &Inventory) -> bool {
+        self.active.is_none()
+            && !self.is_completed(node)
+            && node.get_prerequisites().iter().all(|prerequisite| self.is_completed(prerequisite))
+            && node.get_cost().iter().all(|(item, amount)| inventory.get_item_amount(item) >= *amount)
+    }
+
+    /// Skills no research node unlocks are there from the start.
+    pub fn is_skill_available(&self, skill: &SkillArchetype) -> bool {
+        ResearchNode::iter()
+            .filter(|node| node.get_unlock() == ResearchUnlock::Skill(skill.clone()))
+            .all(|node| self.is_completed(&node))
+    }
+
+    pub fn is_job_available(&self, job_archetype: &JobArchetype) -> bool {
+        if !self.is_skill_available(&job_archetype.get_skill_type()) {
+            return false;
+        }
+
+        ResearchNode::iter()
+            .filter(|node| matches!(node.get_unlock(), ResearchUnlock::Recipes(jobs) if jobs.contains(job_archetype)))
+            .all(|node| self.is_completed(&node))
+    }
+
+    /// Number of job slots that can be unlocked, counting from the first one.
+    pub fn slot_limit(&self) -> usize {
+        self.completed.iter()
+            .filter_map(|node| match node.get_unlock() {
+                ResearchUnlock::JobSlots(slots) => Some(slots),
+                _ => None,
+            })
+            .fold(BASE_JOB_SLOTS, usize::max)
+    }
+
+    pub fn rule_limit(&self) -> usize {
+        self.completed.iter()
+            .filter_map(|node| match node.get_unlock() {
+                ResearchUnlock::Rules(rules) => Some(rules),
+                _ => None,
+            })
+            .fold(BASE_RULES, usize::max)
+    }
+
+    pub fn get_modifiers(&self) -> JobModifiers {
+        self.completed.iter()
+            .filter_map(|node| match node.get_unlock() {
+                ResearchUnlock::HyperBoost(multiplier) => Some(JobModifiers { hyper_multiplier: multiplier, ..JobModifiers::none() }),
+                _ => None,
+            })
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+
+    /// Advances the active research and returns the node if it just finished.
+    pub fn update(&mut self, dt: f32) -> Option<ResearchNode> {
+        let active = self.active.as_mut()?;
+        active.elapsed_seconds += dt as f64;
+
+        if active.elapsed_seconds < active.node.get_duration() {
+            return None;
+        }
+
+        let node = active.node;
+        self.active = None;
+        self.completed.push(node);
+
+        Some(node)
+    }
+}
+
+/// Pays for the node and starts researching it.
+pub fn start_research(state: &mut GameState, node: ResearchNode) -> bool {
+    if !state.research.can_start(&node, &state.inventory) {
+        return false;
+    }
+
+    for (item, amount) in node.get_cost() {
+        state.inventory.add_item(item, -amount);
+    }
+
+    state.research.active = Some(ActiveResearch { node, elapsed_seconds: 0.0 });
+
+    true
+}
+
+pub fn research_toast(node: &ResearchNode) -> Toast {
+    Toast {
+        text: format!("Research complete: {}", node.get_name()),
+        remaining_seconds: TOAST_SECONDS,
+    }
+}
+
+pub fn build_research_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let research = &state.research;
+
+    let title = match &research.active {
+        Some(active) => format!("Research - {} ({} left)", active.node.get_name(),
+            pretty_duration(active.node.get_duration() - active.elapsed_seconds)),
+        None => "Research - pick a node to study".to_string(),
+    };
+
+    elements.push(UiElement::Text {
+        content: title,
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    let pan_buttons = [
+        ("<", Vec2::new(PAN_STEP, 0.0)),
+        (">", Vec2::new(-PAN_STEP, 0.0)),
+        ("^", Vec2::new(0.0, PAN_STEP)),
+        ("v", Vec2::new(0.0, -PAN_STEP)),
+    ];
+
+    for (i, (label, delta)) in pan_buttons.iter().enumerate() {
+        elements.push(button(assets, UiRect::new(rect.x + rect.w - padding - 4.0 * 36.0 + i as f32 * 36.0, rect.y + padding, 32.0, 32.0),
+            label, true, Intent::PanResearch(delta.x, delta.y), None));
+    }
+
+    // the graph moves with the pan offset and is clipped to the area below the title
+    let graph = UiRect::new(rect.x + padding, rect.y + padding + 48.0, rect.w - padding * 2.0, rect.h - padding * 2.0 - 48.0);
+    let clip = (graph.x as i32, graph.y as i32, graph.w as i32, graph.h as i32);
+    let origin = Vec2::new(graph.x + 8.0, graph.y + 8.0) + research.pan;
+    let node_position = |node: &ResearchNode| {
+        let (column, row) = node.get_position();
+        Vec2::new(origin.x + column as f32 * COLUMN_WIDTH, origin.y + row as f32 * ROW_HEIGHT)
+    };
+
+    elements.push(UiElement::Scissor { clip: Some(clip) });
+
+    // edges first, so the nodes are drawn on top
+    for node in ResearchNode::iter() {
+        let to = node_position(&node);
+
+        for prerequisite in node.get_prerequisites() {
+            let from = node_position(&prerequisite);
+            let color = if research.is_completed(&prerequisite) { palette::PROGRESS_COLOR.get_color() } else { palette::BORDER.get_color() };
+            let start = Vec2::new(from.x + NODE_WIDTH, from.y + NODE_HEIGHT / 2.0);
+            let end = Vec2::new(to.x, to.y + NODE_HEIGHT / 2.0);
+            let middle_x = (start.x + end.x) / 2.0;
+
+            elements.extend([
+                edge(start.x, start.y, middle_x, start.y, color),
+                edge(middle_x, start.y.min(end.y), middle_x, start.y.max(end.y), color),
+                edge(middle_x, end.y, end.x, end.y, color),
+            ]);
+        }
+    }
+
+    for node in ResearchNode::iter() {
+        let position = node_position(&node);
+        let completed = research.is_completed(&node);
+        let active = research.active.as_ref().filter(|active| active.node == node);
+
+        elements.push(UiElement::Rectangle {
+            x: position.x,
+            y: position.y,
+            width: NODE_WIDTH,
+            height: NODE_HEIGHT,
+            color: if completed { PaletteC::Peach.get_color() } else { palette::IMAGE_BACKGROUND.get_color() },
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::Text {
+            content: node.get_name().to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x: position.x + 8.0,
+            y: position.y + 18.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::Text {
+            content: node.get_description(),
+            font: assets.fonts.text.clone(),
+            x: position.x + 8.0,
+            y: position.y + 36.0,
+            font_size: 12.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        if completed {
+            continue;
+        }
+
+        if let Some(active) = active {
+            elements.push(UiElement::ProgressBar {
+                x: position.x + 8.0,
+                y: position.y + NODE_HEIGHT - 24.0,
+                width: NODE_WIDTH - 16.0,
+                height: 14.0,
+                progress: active.progress(),
+                background_color: palette::BAR_BACKGROUND.get_color(),
+                foreground_color: palette::PROGRESS_COLOR.get_color(),
+                border_style: BorderStyle::Solid,
+            });
+            continue;
+        }
+
+        let cost = node.get_cost().iter()
+            .map(|(item, amount)| format!("{} {}", amount, item.get_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        elements.push(UiElement::Text {
+            content: format!("{}, {}", cost, pretty_duration(node.get_duration())),
+            font: assets.fonts.text.clone(),
+            x: position.x + 8.0,
+            y: position.y + 54.0,
+            font_size: 12.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(button(assets, UiRect::new(position.x + 8.0, position.y + NODE_HEIGHT - 24.0, NODE_WIDTH - 16.0, 18.0),
+            "Research", research.can_start(&node, &state.inventory), Intent::StartResearch(node), Some(clip)));
+    }
+
+    elements.push(UiElement::Scissor { clip: None });
+
+    elements
+}
+
+/// Axis aligned line between two points, drawn as a thin rectangle.
+fn edge(x1: f32, y1: f32, x2: f32, y2: f32, color: Color) -> UiElement {
+    UiElement::Rectangle {
+        x: x1 - 1.0,
+        y: y1 - 1.0,
+        width: (x2 - x1).abs() + 2.0,
+        height: (y2 - y1).abs() + 2.0,
+        color,
+        border_style: BorderStyle::None,
+    }
+}
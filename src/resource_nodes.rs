@@ -0,0 +1,169 @@
+use crate::toasts::{Toast, TOAST_SECONDS};
+use crate::game::{GameState, Item};
+use crate::job::{JobArchetype, LumberingJobArchetype, MiningJobArchetype};
+use crate::rng::Rng;
+use strum::IntoEnumIterator;
+
+/// Actions a grove holds when fully grown.
+pub const TREE_CAPACITY: f64 = 100.0;
+
+/// Actions a grove grows back per second, enough to keep one job at base speed going.
+pub const TREE_REGROWTH_PER_SECOND: f64 = 0.25;
+
+/// Actions in the vein every new game starts with.
+pub const FIRST_VEIN_CAPACITY: f64 = 300.0;
+
+/// Range of actions in a newly found vein.
+pub const PROSPECTED_VEIN_CAPACITY: (f64, f64) = (200.0, 500.0);
+
+pub const PROSPECT_SECONDS: f64 = 60.0;
+pub const PROSPECT_COST: i64 = 50;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum NodeKind {
+    Tree,
+    Vein,
+}
+
+impl NodeKind {
+    pub fn for_job(job_archetype: &JobArchetype) -> Option<NodeKind> {
+        match job_archetype {
+            JobArchetype::Lumbering(_) => Some(NodeKind::Tree),
+            JobArchetype::Mining(_) => Some(NodeKind::Vein),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        match self {
+            NodeKind::Tree => "Grove",
+            NodeKind::Vein => "Vein",
+        }
+    }
+}
+
+/// What is left to gather for one job. Every action takes one off.
+#[derive(Clone, PartialEq, Debug)]
+pub struct ResourceNode {
+    pub job_archetype: JobArchetype,
+    pub remaining: f64,
+    pub capacity: f64,
+}
+
+impl ResourceNode {
+    pub fn kind(&self) -> NodeKind {
+        NodeKind::for_job(&self.job_archetype).unwrap_or(NodeKind::Tree)
+    }
+
+    pub fn health(&self) -> f64 {
+        (self.remaining / self.capacity).clamp(0.0, 1.0)
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining < 1.0
+    }
+}
+
+/// A search for a new vein, which replaces the old one once found.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Prospecting {
+    pub job_archetype: JobArchetype,
+    pub remaining_seconds: f64,
+}
+
+pub struct ResourceNodes {
+    pub nodes: Vec<ResourceNode>,
+    pub prospecting: Option<Prospecting>,
+}
+
+impl ResourceNodes {
+    pub fn new() -> Self {
+        let trees = LumberingJobArchetype::iter().map(|job| ResourceNode {
+            job_archetype: JobArchetype::Lumbering(job),
+            remaining: TREE_CAPACITY,
+            capacity: TREE_CAPACITY,
+        });
+
+        let veins = MiningJobArchetype::iter().map(|job| ResourceNode {
+            job_archetype: JobArchetype::Mining(job),
+            remaining: FIRST_VEIN_CAPACITY,
+            capacity: FIRST_VEIN_CAPACITY,
+        });
+
+        Self {
+            nodes: trees.chain(veins).collect(),
+            prospecting: None,
+        }
+    }
+
+    pub fn get_node(&self, job_archetype: &JobArchetype) -> Option<&ResourceNode> {
+        self.nodes.iter().find(|node| node.job_archetype == *job_archetype)
+    }
+
+    pub fn get_node_mut(&mut self, job_archetype: &JobArchetype) -> Option<&mut ResourceNode> {
+        self.nodes.iter_mut().find(|node| node.job_archetype == *job_archetype)
+    }
+
+    /// Jobs without a node never run out.
+    pub fn has_resources(&self, job_archetype: &JobArchetype) -> bool {
+        self.get_node(job_archetype).is_none_or(|node| !node.is_exhausted())
+    }
+
+    pub fn deplete(&mut self, job_archetype: &JobArchetype) {
+        if let Some(node) = self.get_node_mut(job_archetype) {
+            node.remaining = (node.remaining - 1.0).max(0.0);
+        }
+    }
+
+    /// Regrows the trees and moves the prospecting along. Returns the node of a newly found vein.
+    pub fn update(&mut self, rng: &mut Rng, dt: f32) -> Option<ResourceNode> {
+        for node in self.nodes.iter_mut().filter(|node| node.kind() == NodeKind::Tree) {
+            node.remaining = (node.remaining + TREE_REGROWTH_PER_SECOND * dt as f64).min(node.capacity);
+        }
+
+        let prospecting = self.prospecting.as_mut()?;
+        prospecting.remaining_seconds -= dt as f64;
+
+        if prospecting.remaining_seconds > 0.0 {
+            return None;
+        }
+
+        let job_archetype = self.prospecting.take()?.job_archetype;
+        let (min, max) = PROSPECTED_VEIN_CAPACITY;
+        let capacity = rng.range(min, max).round();
+        let node = self.get_node_mut(&job_archetype)?;
+
+        node.remaining = capacity;
+        node.capacity = capacity;
+
+        Some(node.clone())
+    }
+}
+
+pub fn can_prospect(state: &GameState, job_archetype: &JobArchetype) -> bool {
+    NodeKind::for_job(job_archetype) == Some(NodeKind::Vein)
+        && state.resource_nodes.prospecting.is_none()
+        && state.inventory.get_item_amount(&Item::Coin) >= PROSPECT_COST
+}
+
+/// Pays for a search for a new vein. Whatever is left in the current one is lost once it is found.
+pub fn start_prospecting(state: &mut GameState, job_archetype: JobArchetype) -> bool {
+    if !can_prospect(state, &job_archetype) {
+        return false;
+    }
+
+    state.inventory.add_item(Item::Coin, -PROSPECT_COST);
+    state.resource_nodes.prospecting = Some(Prospecting {
+        job_archetype,
+        remaining_seconds: PROSPECT_SECONDS,
+    });
+
+    true
+}
+
+pub fn vein_found_toast(node: &ResourceNode) -> Toast {
+    Toast {
+        text: format!("Found a new {} vein with {:.0} actions", node.job_archetype.get_product().get_name(), node.capacity),
+        remaining_seconds: TOAST_SECONDS,
+    }
+}
@@ -0,0 +1,98 @@
+use crate::assets::AssetId::{RuneBlank, RuneHeart, RuneHeart2, RuneTime, RuneTime2};
+use crate::assets::Assets;
+use crate::game::{GameState, Item};
+use crate::job::JobModifiers;
+use macroquad::prelude::Texture2D;
+use std::mem;
+use strum_macros::EnumIter;
+
+pub const SOCKETS_PER_SLOT: usize = 2;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Rune {
+    Blank,
+    Time,
+    GreaterTime,
+    Heart,
+    GreaterHeart,
+}
+
+impl Rune {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Rune::Blank => "Blank Rune",
+            Rune::Time => "Time Rune",
+            Rune::GreaterTime => "Greater Time Rune",
+            Rune::Heart => "Heart Rune",
+            Rune::GreaterHeart => "Greater Heart Rune",
+        }
+    }
+
+    pub fn get_texture(&self, assets: &Assets) -> Texture2D {
+        match self {
+            Rune::Blank => RuneBlank.get_texture(assets),
+            Rune::Time => RuneTime.get_texture(assets),
+            Rune::GreaterTime => RuneTime2.get_texture(assets),
+            Rune::Heart => RuneHeart.get_texture(assets),
+            Rune::GreaterHeart => RuneHeart2.get_texture(assets),
+        }
+    }
+
+    /// Blank runes still have to be inscribed before they do anything.
+    pub fn is_socketable(&self) -> bool {
+        *self != Rune::Blank
+    }
+
+    pub fn get_modifiers(&self) -> JobModifiers {
+        match self {
+            Rune::Blank => JobModifiers::none(),
+            Rune::Time => JobModifiers { speed_multiplier: 1.15, ..JobModifiers::none() },
+            Rune::GreaterTime => JobModifiers { speed_multiplier: 1.3, ..JobModifiers::none() },
+            Rune::Heart => JobModifiers { yield_multiplier: 1.15, ..JobModifiers::none() },
+            Rune::GreaterHeart => JobModifiers { yield_multiplier: 1.15, hyper_multiplier: 1.5, ..JobModifiers::none() },
+        }
+    }
+}
+
+pub fn socket_modifiers(sockets: &[Option<Rune>]) -> JobModifiers {
+    sockets.iter()
+        .flatten()
+        .map(|rune| rune.get_modifiers())
+        .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+}
+
+/// Short summary of what the socketed runes add, e.g. "+32% speed, +15% yield".
+pub fn describe_modifiers(modifiers: &JobModifiers) -> String {
+    [
+        (modifiers.speed_multiplier, "speed"),
+        (modifiers.yield_multiplier, "yield"),
+        (modifiers.hyper_multiplier, "hyper"),
+    ].iter()
+        .filter(|(multiplier, _)| (*multiplier - 1.0).abs() > 1e-9)
+        .map(|(multiplier, name)| format!("+{:.0}% {}", (multiplier - 1.0) * 100.0, name))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Puts a rune from the inventory into the socket, or empties it. A rune that was in the socket goes back to the inventory.
+pub fn set_socket(state: &mut GameState, slot_index: usize, socket_index: usize, rune: Option<Rune>) -> bool {
+    if socket_index >= SOCKETS_PER_SLOT || state.job_slots.get(slot_index).is_none() {
+        return false;
+    }
+
+    if let Some(rune) = rune {
+        if !rune.is_socketable() || state.inventory.get_item_amount(&Item::Rune(rune)) < 1 {
+            return false;
+        }
+
+        state.inventory.add_item(Item::Rune(rune), -1);
+    }
+
+    let socket = &mut state.job_slots[slot_index].sockets[socket_index];
+
+    if let Some(previous) = mem::replace(socket, rune) {
+        state.inventory.add_item(Item::Rune(previous), 1);
+    }
+
+    true
+}
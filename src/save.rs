@@ -0,0 +1,1011 @@
+use crate::automation::{Rule, RuleAction, RuleCondition};
+use crate::achievements::Achievement;
+use crate::buffs::{Buff, BuffKind};
+use crate::combat::{Enemy, Fight};
+use crate::counts_actions::CountsActions;
+use crate::orders::{Order, OrderReward};
+use crate::prestige::{Prestige, PrestigePerk};
+use crate::research::{ActiveResearch, ResearchNode};
+use crate::resource_nodes::Prospecting;
+use crate::rng::Rng;
+use crate::runes::Rune;
+use crate::shop::Upgrade;
+use crate::equipment::{EquippedTool, Tool};
+use crate::expeditions::{Destination, Expedition};
+use crate::game::{GameState, Item};
+use crate::job::{JobArchetype, JobInstance, JobParameters};
+use crate::job_slot::{JobSlotState, QueuedJob, StopCondition};
+use crate::skill::SkillArchetype;
+use crate::village::Building;
+use crate::villagers::Villager;
+use crate::workers::{Upkeep, Worker, WorkerTrait};
+use macroquad::miniquad::date::now;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::str::SplitWhitespace;
+use strum::IntoEnumIterator;
+
+pub const SAVE_KEY: &str = "tiny-fields-save";
+
+/// Prestige progress lives under its own key, so a reset never touches it.
+pub const PRESTIGE_SAVE_KEY: &str = "tiny-fields-prestige";
+
+/// When the game was last saved, to catch up on the time it was closed.
+pub const SAVED_AT_KEY: &str = "tiny-fields-saved-at";
+
+/// Whitespace separated tokens of a single save line.
+pub struct Tokens<'a> {
+    inner: SplitWhitespace<'a>,
+}
+
+impl<'a> Tokens<'a> {
+    pub fn new(line: &'a str) -> Self {
+        Self { inner: line.split_whitespace() }
+    }
+
+    pub fn next_str(&mut self) -> Option<&'a str> {
+        self.inner.next()
+    }
+
+    pub fn read<T: Persist>(&mut self) -> Option<T> {
+        T::read(self)
+    }
+}
+
+/// A value that can be written to and read back from a save line.
+pub trait Persist: Sized {
+    fn write(&self, out: &mut Vec<String>);
+    fn read(tokens: &mut Tokens) -> Option<Self>;
+}
+
+/// Builds a single save line, starting with the record name.
+pub struct SaveLine {
+    tokens: Vec<String>,
+}
+
+impl SaveLine {
+    pub fn new(record: &str) -> Self {
+        Self { tokens: vec![record.to_string()] }
+    }
+
+    pub fn with<T: Persist>(mut self, value: &T) -> Self {
+        value.write(&mut self.tokens);
+        self
+    }
+
+    pub fn build(self) -> String {
+        self.tokens.join(" ")
+    }
+}
+
+/// Enums are saved by their debug name, e.g. `Wood(Craftwood)`.
+fn find_by_id<T: Debug>(values: impl IntoIterator<Item = T>, id: &str) -> Option<T> {
+    values.into_iter().find(|value| format!("{:?}", value) == id)
+}
+
+impl Persist for i64 {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(self.to_string());
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        tokens.next_str()?.parse().ok()
+    }
+}
+
+impl Persist for u64 {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(self.to_string());
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        tokens.next_str()?.parse().ok()
+    }
+}
+
+impl Persist for i32 {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(self.to_string());
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        tokens.next_str()?.parse().ok()
+    }
+}
+
+impl Persist for usize {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(self.to_string());
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        tokens.next_str()?.parse().ok()
+    }
+}
+
+impl Persist for f64 {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(self.to_string());
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        tokens.next_str()?.parse().ok()
+    }
+}
+
+impl Persist for bool {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(if *self { "1" } else { "0" }.to_string());
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "1" => Some(true),
+            "0" => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl Persist for Item {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Item::all(), tokens.next_str()?)
+    }
+}
+
+impl Persist for JobArchetype {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(JobArchetype::all(), tokens.next_str()?)
+    }
+}
+
+impl Persist for SkillArchetype {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(SkillArchetype::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Tool {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Tool::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for PrestigePerk {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(PrestigePerk::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for ResearchNode {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(ResearchNode::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Rune {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Rune::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Building {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Building::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for OrderReward {
+    fn write(&self, out: &mut Vec<String>) {
+        match self {
+            OrderReward::Coins(coins) => {
+                out.push("Coins".to_string());
+                coins.write(out);
+            }
+            OrderReward::SkillActions(skill, actions) => {
+                out.push("SkillActions".to_string());
+                skill.write(out);
+                actions.write(out);
+            }
+            OrderReward::Item(item, amount) => {
+                out.push("Item".to_string());
+                item.write(out);
+                amount.write(out);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "Coins" => Some(OrderReward::Coins(tokens.read()?)),
+            "SkillActions" => Some(OrderReward::SkillActions(tokens.read()?, tokens.read()?)),
+            "Item" => Some(OrderReward::Item(tokens.read()?, tokens.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Persist for Destination {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Destination::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Enemy {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Enemy::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Villager {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Villager::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for WorkerTrait {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(WorkerTrait::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Upkeep {
+    fn write(&self, out: &mut Vec<String>) {
+        match self {
+            Upkeep::Coins(amount) => {
+                out.push("Coins".to_string());
+                amount.write(out);
+            }
+            Upkeep::Sandwiches(amount) => {
+                out.push("Sandwiches".to_string());
+                amount.write(out);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "Coins" => Some(Upkeep::Coins(tokens.read()?)),
+            "Sandwiches" => Some(Upkeep::Sandwiches(tokens.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Persist for Worker {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(self.name.clone());
+        self.upkeep.write(out);
+        self.upkeep_accumulator.write(out);
+        self.unpaid_seconds.write(out);
+        self.experience.level.write(out);
+        self.experience.actions_done_current_level.write(out);
+        self.experience.actions_done_total.write(out);
+        self.traits.len().write(out);
+
+        for worker_trait in &self.traits {
+            worker_trait.write(out);
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        let name = tokens.next_str()?.to_string();
+        let upkeep = tokens.read()?;
+        let upkeep_accumulator = tokens.read()?;
+        let unpaid_seconds = tokens.read()?;
+        let (level, actions_done_current_level, actions_done_total) = (tokens.read()?, tokens.read()?, tokens.read()?);
+        let count: usize = tokens.read()?;
+        let mut traits = vec![];
+
+        for _ in 0..count {
+            traits.push(tokens.read()?);
+        }
+
+        let mut worker = Worker::new(&name, traits, upkeep);
+        worker.upkeep_accumulator = upkeep_accumulator;
+        worker.unpaid_seconds = unpaid_seconds;
+        worker.experience.restore(level, actions_done_current_level, actions_done_total);
+
+        Some(worker)
+    }
+}
+
+impl Persist for Order {
+    fn write(&self, out: &mut Vec<String>) {
+        self.remaining_seconds.write(out);
+        self.reward.write(out);
+        self.requests.len().write(out);
+
+        for (item, amount) in &self.requests {
+            item.write(out);
+            amount.write(out);
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        let remaining_seconds = tokens.read()?;
+        let reward = tokens.read()?;
+        let count: usize = tokens.read()?;
+        let mut requests = vec![];
+
+        for _ in 0..count {
+            requests.push((tokens.read()?, tokens.read()?));
+        }
+
+        Some(Order { requests, reward, remaining_seconds })
+    }
+}
+
+impl Persist for Achievement {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Achievement::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for Upgrade {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(Upgrade::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for BuffKind {
+    fn write(&self, out: &mut Vec<String>) {
+        out.push(format!("{:?}", self));
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        find_by_id(BuffKind::iter(), tokens.next_str()?)
+    }
+}
+
+impl Persist for StopCondition {
+    fn write(&self, out: &mut Vec<String>) {
+        match self {
+            StopCondition::Never => out.push("Never".to_string()),
+            StopCondition::ActionsCompleted(actions) => {
+                out.push("ActionsCompleted".to_string());
+                actions.write(out);
+            }
+            StopCondition::ItemAtLeast(item, amount) => {
+                out.push("ItemAtLeast".to_string());
+                item.write(out);
+                amount.write(out);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "Never" => Some(StopCondition::Never),
+            "ActionsCompleted" => Some(StopCondition::ActionsCompleted(tokens.read()?)),
+            "ItemAtLeast" => Some(StopCondition::ItemAtLeast(tokens.read()?, tokens.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Persist for RuleCondition {
+    fn write(&self, out: &mut Vec<String>) {
+        match self {
+            RuleCondition::ItemBelow(item, amount) => {
+                out.push("ItemBelow".to_string());
+                item.write(out);
+                amount.write(out);
+            }
+            RuleCondition::ItemAtLeast(item, amount) => {
+                out.push("ItemAtLeast".to_string());
+                item.write(out);
+                amount.write(out);
+            }
+            RuleCondition::SkillLevelAtLeast(skill, level) => {
+                out.push("SkillLevelAtLeast".to_string());
+                skill.write(out);
+                level.write(out);
+            }
+            RuleCondition::HyperCharged(index) => {
+                out.push("HyperCharged".to_string());
+                index.write(out);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "ItemBelow" => Some(RuleCondition::ItemBelow(tokens.read()?, tokens.read()?)),
+            "ItemAtLeast" => Some(RuleCondition::ItemAtLeast(tokens.read()?, tokens.read()?)),
+            "SkillLevelAtLeast" => Some(RuleCondition::SkillLevelAtLeast(tokens.read()?, tokens.read()?)),
+            "HyperCharged" => Some(RuleCondition::HyperCharged(tokens.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Persist for RuleAction {
+    fn write(&self, out: &mut Vec<String>) {
+        match self {
+            RuleAction::SwitchSlot(index, job_archetype) => {
+                out.push("SwitchSlot".to_string());
+                index.write(out);
+                job_archetype.write(out);
+            }
+            RuleAction::EnableHyper(index) => {
+                out.push("EnableHyper".to_string());
+                index.write(out);
+            }
+            RuleAction::PauseSlot(index) => {
+                out.push("PauseSlot".to_string());
+                index.write(out);
+            }
+            RuleAction::ResumeSlot(index) => {
+                out.push("ResumeSlot".to_string());
+                index.write(out);
+            }
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "SwitchSlot" => Some(RuleAction::SwitchSlot(tokens.read()?, tokens.read()?)),
+            "EnableHyper" => Some(RuleAction::EnableHyper(tokens.read()?)),
+            "PauseSlot" => Some(RuleAction::PauseSlot(tokens.read()?)),
+            "ResumeSlot" => Some(RuleAction::ResumeSlot(tokens.read()?)),
+            _ => None,
+        }
+    }
+}
+
+impl Persist for Rule {
+    fn write(&self, out: &mut Vec<String>) {
+        self.enabled.write(out);
+        self.condition.write(out);
+        self.action.write(out);
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        Some(Rule {
+            enabled: tokens.read()?,
+            condition: tokens.read()?,
+            action: tokens.read()?,
+        })
+    }
+}
+
+/// Picking states are transient, a slot that was being set up is loaded as empty.
+impl Persist for JobSlotState {
+    fn write(&self, out: &mut Vec<String>) {
+        match self {
+            JobSlotState::Locked => out.push("Locked".to_string()),
+            JobSlotState::RunningJob(job_instance) => {
+                out.push("RunningJob".to_string());
+                job_instance.job_archetype.write(out);
+                job_instance.running.write(out);
+                job_instance.time_accumulator.write(out);
+                job_instance.has_paid_resources.write(out);
+                job_instance.hyper_mode.actions_counter.write(out);
+                job_instance.hyper_mode.is_enabled.write(out);
+                job_instance.hyper_mode.hyper_time_accumulator.write(out);
+            }
+            _ => out.push("Empty".to_string()),
+        }
+    }
+
+    fn read(tokens: &mut Tokens) -> Option<Self> {
+        match tokens.next_str()? {
+            "Locked" => Some(JobSlotState::Locked),
+            "Empty" => Some(JobSlotState::Empty),
+            "RunningJob" => {
+                let mut job_instance = JobInstance::new(JobParameters { job_archetype: tokens.read()? });
+                job_instance.running = tokens.read()?;
+                job_instance.time_accumulator = tokens.read()?;
+                job_instance.has_paid_resources = tokens.read()?;
+                job_instance.hyper_mode.actions_counter = tokens.read()?;
+                job_instance.hyper_mode.is_enabled = tokens.read().unwrap_or(false);
+                job_instance.hyper_mode.hyper_time_accumulator = tokens.read().unwrap_or(0.0);
+                job_instance.action_progress.set(job_instance.time_accumulator / job_instance.job_archetype.base_duration());
+                Some(JobSlotState::RunningJob(job_instance))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn write_counter(line: SaveLine, counter: &CountsActions) -> SaveLine {
+    line.with(&counter.level)
+        .with(&counter.actions_done_current_level)
+        .with(&counter.actions_done_total)
+}
+
+fn read_counter(tokens: &mut Tokens, counter: &mut CountsActions) -> Option<()> {
+    counter.restore(tokens.read()?, tokens.read()?, tokens.read()?);
+    Some(())
+}
+
+impl GameState {
+    pub fn to_save_string(&self) -> String {
+        let mut lines = vec![];
+
+        for (item, amount) in &self.inventory.item_amounts {
+            lines.push(SaveLine::new("item").with(item).with(amount).build());
+        }
+
+        for instance in &self.skill_archetype_instances.instances {
+            lines.push(write_counter(SaveLine::new("skill").with(&instance.skill_type), &instance.actions_counter).build());
+        }
+
+        for instance in &self.job_archetype_instances.instances {
+            lines.push(write_counter(SaveLine::new("job").with(&instance.job_archetype), &instance.action_counter).build());
+        }
+
+        for slot in &self.job_slots {
+            lines.push(SaveLine::new("slot").with(&slot.index).with(&slot.state).build());
+            lines.push(SaveLine::new("goal").with(&slot.index).with(&slot.queue.stop_condition).with(&slot.queue.actions_completed).build());
+
+            for (socket_index, rune) in slot.sockets.iter().enumerate() {
+                if let Some(rune) = rune {
+                    lines.push(SaveLine::new("socket").with(&slot.index).with(&socket_index).with(rune).build());
+                }
+            }
+
+            for queued_job in &slot.queue.upcoming {
+                lines.push(SaveLine::new("queued").with(&slot.index).with(&queued_job.job_archetype).with(&queued_job.stop_condition).build());
+            }
+        }
+
+        for rule in &self.automation.rules {
+            lines.push(SaveLine::new("rule").with(rule).build());
+        }
+
+        for equipped in &self.equipment.equipped {
+            lines.push(SaveLine::new("tool").with(&equipped.tool).with(&equipped.durability).build());
+        }
+
+        for buff in &self.buffs.active {
+            lines.push(SaveLine::new("buff").with(&buff.kind).with(&buff.remaining_seconds).build());
+        }
+
+        for (upgrade, level) in &self.shop.upgrade_levels {
+            lines.push(SaveLine::new("upgrade").with(upgrade).with(level).build());
+        }
+
+        for (building, level) in &self.village.building_levels {
+            lines.push(SaveLine::new("building").with(building).with(level).build());
+        }
+
+        for (col, row) in &self.territory.claimed {
+            lines.push(SaveLine::new("tile").with(col).with(row).build());
+        }
+
+        for (villager, affection) in &self.villagers.affection {
+            lines.push(SaveLine::new("affection").with(villager).with(affection).build());
+        }
+
+        for achievement in &self.achievements.unlocked {
+            lines.push(SaveLine::new("achievement").with(achievement).build());
+        }
+
+        for (item, amount) in &self.achievements.produced {
+            lines.push(SaveLine::new("produced").with(item).with(amount).build());
+        }
+
+        lines.push(SaveLine::new("hyper_activations").with(&self.achievements.hyper_activations).build());
+
+        lines.push(SaveLine::new("rng").with(&self.rng.state).build());
+        lines.push(SaveLine::new("order_timer").with(&self.order_board.refresh_timer).build());
+
+        for order in &self.order_board.orders {
+            lines.push(SaveLine::new("order").with(order).build());
+        }
+
+        for node in &self.research.completed {
+            lines.push(SaveLine::new("research").with(node).build());
+        }
+
+        lines.push(SaveLine::new("worker_timer").with(&self.workers.refresh_timer).build());
+
+        for worker in &self.workers.hired {
+            lines.push(SaveLine::new("worker").with(worker).build());
+
+            if let Some(slot_index) = worker.assigned_slot {
+                lines.push(SaveLine::new("worker_slot").with(&slot_index).build());
+            }
+        }
+
+        for candidate in &self.workers.candidates {
+            lines.push(SaveLine::new("candidate").with(candidate).build());
+        }
+
+        for expedition in &self.expeditions.active {
+            lines.push(SaveLine::new("expedition").with(&expedition.destination).with(&expedition.remaining_seconds).build());
+
+            for worker in &expedition.party {
+                lines.push(SaveLine::new("party").with(worker).build());
+            }
+        }
+
+        lines.push(SaveLine::new("spoil_age").with(&self.spoilage.age_accumulator).build());
+
+        for (item, buckets) in &self.spoilage.batches {
+            let mut line = SaveLine::new("batch").with(item).with(&buckets.len());
+
+            for amount in buckets {
+                line = line.with(amount);
+            }
+
+            lines.push(line.build());
+        }
+
+        for node in &self.resource_nodes.nodes {
+            lines.push(SaveLine::new("node").with(&node.job_archetype).with(&node.remaining).with(&node.capacity).build());
+        }
+
+        if let Some(prospecting) = &self.resource_nodes.prospecting {
+            lines.push(SaveLine::new("prospecting").with(&prospecting.job_archetype).with(&prospecting.remaining_seconds).build());
+        }
+
+        lines.push(write_counter(SaveLine::new("combat"), &self.combat.experience)
+            .with(&self.combat.hp)
+            .with(&self.combat.kills)
+            .with(&self.combat.use_abilities)
+            .build());
+
+        if let Some(fight) = &self.combat.fight {
+            lines.push(SaveLine::new("fight").with(&fight.enemy).with(&fight.enemy_hp).build());
+        }
+
+        if let Some(active) = &self.research.active {
+            lines.push(SaveLine::new("research_active").with(&active.node).with(&active.elapsed_seconds).build());
+        }
+
+        lines.push(SaveLine::new("market")
+            .with(&self.market.rng.state)
+            .with(&self.market.tick_accumulator)
+            .with(&self.market.coin_accumulator)
+            .build());
+
+        for (item, price) in &self.market.prices {
+            lines.push(SaveLine::new("price")
+                .with(item)
+                .with(&price.saturation)
+                .with(&price.spike_factor)
+                .with(&price.spike_ticks_left)
+                .build());
+        }
+
+        lines.join("\n")
+    }
+
+    /// Builds a game from a save. Lines that can't be read are skipped, so older saves keep working.
+    pub fn from_save_string(save: &str) -> GameState {
+        let mut state = GameState::new();
+
+        for line in save.lines() {
+            state.load_line(&mut Tokens::new(line));
+        }
+
+        state
+    }
+
+    fn load_line(&mut self, tokens: &mut Tokens) -> Option<()> {
+        match tokens.next_str()? {
+            "item" => {
+                let item: Item = tokens.read()?;
+                let amount: i64 = tokens.read()?;
+                self.inventory.item_amounts.insert(item, amount);
+            }
+            "skill" => {
+                let skill_type: SkillArchetype = tokens.read()?;
+                read_counter(tokens, &mut self.skill_archetype_instances.get_skill_by_type_mut(&skill_type).actions_counter)?;
+            }
+            "job" => {
+                let job_archetype: JobArchetype = tokens.read()?;
+                read_counter(tokens, &mut self.job_archetype_instances.get_archetype_mut(&job_archetype).action_counter)?;
+            }
+            "slot" => {
+                let index: usize = tokens.read()?;
+                let state: JobSlotState = tokens.read()?;
+                self.job_slots.get_mut(index)?.state = state;
+            }
+            "goal" => {
+                let index: usize = tokens.read()?;
+                let stop_condition: StopCondition = tokens.read()?;
+                let actions_completed: i64 = tokens.read()?;
+                let queue = &mut self.job_slots.get_mut(index)?.queue;
+                queue.stop_condition = stop_condition;
+                queue.actions_completed = actions_completed;
+            }
+            "socket" => {
+                let index: usize = tokens.read()?;
+                let socket_index: usize = tokens.read()?;
+                let rune: Rune = tokens.read()?;
+                *self.job_slots.get_mut(index)?.sockets.get_mut(socket_index)? = Some(rune);
+            }
+            "queued" => {
+                let index: usize = tokens.read()?;
+                let queued_job = QueuedJob {
+                    job_archetype: tokens.read()?,
+                    stop_condition: tokens.read()?,
+                };
+                self.job_slots.get_mut(index)?.queue.upcoming.push_back(queued_job);
+            }
+            "rule" => {
+                let rule: Rule = tokens.read()?;
+                self.automation.rules.push(rule);
+            }
+            "tool" => {
+                let equipped = EquippedTool {
+                    tool: tokens.read()?,
+                    durability: tokens.read()?,
+                };
+                self.equipment.equipped.push(equipped);
+            }
+            "buff" => {
+                let buff = Buff {
+                    kind: tokens.read()?,
+                    remaining_seconds: tokens.read()?,
+                };
+                self.buffs.active.push(buff);
+            }
+            "upgrade" => {
+                let upgrade: Upgrade = tokens.read()?;
+                let level: i64 = tokens.read()?;
+                self.shop.upgrade_levels.insert(upgrade, level);
+            }
+            "building" => {
+                let building: Building = tokens.read()?;
+                let level: i64 = tokens.read()?;
+                self.village.building_levels.insert(building, level);
+            }
+            "tile" => {
+                let position: (usize, usize) = (tokens.read()?, tokens.read()?);
+                if !self.territory.claimed.contains(&position) {
+                    self.territory.claimed.push(position);
+                }
+            }
+            "affection" => {
+                let villager: Villager = tokens.read()?;
+                let affection: i64 = tokens.read()?;
+                self.villagers.affection.insert(villager, affection);
+            }
+            "achievement" => {
+                let achievement: Achievement = tokens.read()?;
+                self.achievements.unlocked.push(achievement);
+            }
+            "produced" => {
+                let item: Item = tokens.read()?;
+                let amount: i64 = tokens.read()?;
+                self.achievements.produced.insert(item, amount);
+            }
+            "hyper_activations" => {
+                self.achievements.hyper_activations = tokens.read()?;
+            }
+            "rng" => {
+                self.rng = Rng::new(tokens.read()?);
+            }
+            "order_timer" => {
+                self.order_board.refresh_timer = tokens.read()?;
+            }
+            "order" => {
+                let order: Order = tokens.read()?;
+                self.order_board.orders.push(order);
+            }
+            "research" => {
+                let node: ResearchNode = tokens.read()?;
+                self.research.completed.push(node);
+            }
+            "research_active" => {
+                self.research.active = Some(ActiveResearch {
+                    node: tokens.read()?,
+                    elapsed_seconds: tokens.read()?,
+                });
+            }
+            "worker_timer" => {
+                self.workers.refresh_timer = tokens.read()?;
+            }
+            "worker" => {
+                let worker: Worker = tokens.read()?;
+                self.workers.hired.push(worker);
+            }
+            "worker_slot" => {
+                // belongs to the worker read just before
+                let slot_index: usize = tokens.read()?;
+                self.workers.hired.last_mut()?.assigned_slot = Some(slot_index);
+            }
+            "candidate" => {
+                let candidate: Worker = tokens.read()?;
+                self.workers.candidates.push(candidate);
+            }
+            "expedition" => {
+                let expedition = Expedition {
+                    destination: tokens.read()?,
+                    remaining_seconds: tokens.read()?,
+                    party: vec![],
+                };
+                self.expeditions.active.push(expedition);
+            }
+            "party" => {
+                // belongs to the expedition read just before
+                let worker: Worker = tokens.read()?;
+                self.expeditions.active.last_mut()?.party.push(worker);
+            }
+            "spoil_age" => {
+                self.spoilage.age_accumulator = tokens.read()?;
+            }
+            "batch" => {
+                let item: Item = tokens.read()?;
+                let count: usize = tokens.read()?;
+                let mut buckets = VecDeque::new();
+
+                for _ in 0..count {
+                    buckets.push_back(tokens.read()?);
+                }
+
+                self.spoilage.batches.insert(item, buckets);
+            }
+            "node" => {
+                let job_archetype: JobArchetype = tokens.read()?;
+                let node = self.resource_nodes.get_node_mut(&job_archetype)?;
+                node.remaining = tokens.read()?;
+                node.capacity = tokens.read()?;
+            }
+            "prospecting" => {
+                self.resource_nodes.prospecting = Some(Prospecting {
+                    job_archetype: tokens.read()?,
+                    remaining_seconds: tokens.read()?,
+                });
+            }
+            "combat" => {
+                read_counter(tokens, &mut self.combat.experience)?;
+                self.combat.hp = tokens.read()?;
+                self.combat.kills = tokens.read()?;
+                self.combat.use_abilities = tokens.read()?;
+            }
+            "fight" => {
+                let mut fight = Fight::new(tokens.read()?);
+                fight.enemy_hp = tokens.read()?;
+                self.combat.fight = Some(fight);
+            }
+            "market" => {
+                self.market.rng = Rng::new(tokens.read()?);
+                self.market.tick_accumulator = tokens.read()?;
+                self.market.coin_accumulator = tokens.read()?;
+            }
+            "price" => {
+                let item: Item = tokens.read()?;
+                let price = self.market.prices.get_mut(&item)?;
+                price.saturation = tokens.read()?;
+                price.spike_factor = tokens.read()?;
+                price.spike_ticks_left = tokens.read()?;
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+impl Prestige {
+    pub fn to_save_string(&self) -> String {
+        let mut lines = vec![
+            SaveLine::new("renown").with(&self.renown).build(),
+            SaveLine::new("resets").with(&self.resets).build(),
+            SaveLine::new("lifetime_actions").with(&self.lifetime_actions).build(),
+        ];
+
+        for (perk, level) in &self.perk_levels {
+            lines.push(SaveLine::new("perk").with(perk).with(level).build());
+        }
+
+        lines.join("\n")
+    }
+
+    pub fn from_save_string(save: &str) -> Prestige {
+        let mut prestige = Prestige::new();
+
+        for line in save.lines() {
+            prestige.load_line(&mut Tokens::new(line));
+        }
+
+        prestige
+    }
+
+    fn load_line(&mut self, tokens: &mut Tokens) -> Option<()> {
+        match tokens.next_str()? {
+            "renown" => self.renown = tokens.read()?,
+            "resets" => self.resets = tokens.read()?,
+            "lifetime_actions" => self.lifetime_actions = tokens.read()?,
+            "perk" => {
+                let perk: PrestigePerk = tokens.read()?;
+                let level: i64 = tokens.read()?;
+                self.perk_levels.insert(perk, level);
+            }
+            _ => return None,
+        }
+
+        Some(())
+    }
+}
+
+pub fn save_game(state: &GameState) {
+    let storage = &mut quad_storage::STORAGE.lock().unwrap();
+    storage.set(SAVE_KEY, &state.to_save_string());
+    storage.set(PRESTIGE_SAVE_KEY, &state.prestige.to_save_string());
+    storage.set(SAVED_AT_KEY, &now().to_string());
+}
+
+/// Seconds since the last save, or zero for a new game.
+pub fn offline_seconds() -> f64 {
+    let storage = quad_storage::STORAGE.lock().unwrap();
+
+    storage.get(SAVED_AT_KEY)
+        .and_then(|saved_at| saved_at.parse::<f64>().ok())
+        .map(|saved_at| (now() - saved_at).max(0.0))
+        .unwrap_or(0.0)
+}
+
+/// Loads the current run and puts the prestige progress back on top of it.
+pub fn load_game() -> Option<GameState> {
+    let storage = quad_storage::STORAGE.lock().unwrap();
+    let mut state = GameState::from_save_string(&storage.get(SAVE_KEY)?);
+
+    if let Some(prestige) = storage.get(PRESTIGE_SAVE_KEY) {
+        state.prestige = Prestige::from_save_string(&prestige);
+    }
+
+    Some(state)
+}
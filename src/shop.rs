@@ -0,0 +1,421 @@
+use crate::assets::AssetId::{BagOfCoins, ParchmentFrame};
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{pretty_duration, GameState, Intent, Item, UiRect, WoodItem};
+use crate::job::JobModifiers;
+use crate::job_slot::JobSlotState;
+use crate::market::{base_price, MARKET_TICK_SECONDS, MAX_SPIKE_FACTOR, PRICE_HISTORY_LENGTH};
+use crate::palette;
+use crate::palette::PaletteC;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Buying costs this many times the base price of the item, no matter how the market moves.
+pub const BUY_PRICE_FACTOR: i64 = 3;
+pub const BUY_AMOUNT: i64 = 10;
+
+pub const MAX_LONGER_SHIFTS: i64 = 5;
+
+/// Raw materials the shop has in stock.
+pub const SHOP_STOCK: [Item; 6] = [
+    Item::Wood(WoodItem::Craftwood),
+    Item::Wood(WoodItem::Graintree),
+    Item::Wood(WoodItem::Kindlewood),
+    Item::IronOre,
+    Item::Herb,
+    Item::Meat,
+];
+
+pub fn buy_price(item: &Item) -> Option<i64> {
+    if !SHOP_STOCK.contains(item) {
+        return None;
+    }
+
+    base_price(item).map(|price| price * BUY_PRICE_FACTOR)
+}
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Upgrade {
+    // One-time upgrades
+    ExtraSlot,
+    QuickHands,
+    HyperFocus,
+
+    // Repeatable upgrades
+    SharperTools,
+    BiggerBaskets,
+    LongerShifts,
+}
+
+impl Upgrade {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Upgrade::ExtraSlot => "Extra Slot",
+            Upgrade::QuickHands => "Quick Hands",
+            Upgrade::HyperFocus => "Hyper Focus",
+            Upgrade::SharperTools => "Sharper Tools",
+            Upgrade::BiggerBaskets => "Bigger Baskets",
+            Upgrade::LongerShifts => "Longer Shifts",
+        }
+    }
+
+    pub fn get_description(&self) -> &str {
+        match self {
+            Upgrade::ExtraSlot => "Unlocks the next locked slot",
+            Upgrade::QuickHands => "All jobs take 20% less time",
+            Upgrade::HyperFocus => "Hyper mode is 50% stronger",
+            Upgrade::SharperTools => "+5% speed per level",
+            Upgrade::BiggerBaskets => "+5% yield per level",
+            Upgrade::LongerShifts => "+1 time slot per level",
+        }
+    }
+
+    /// One-time upgrades can only be bought once, repeatable ones have no limit.
+    pub fn max_level(&self) -> Option<i64> {
+        match self {
+            Upgrade::ExtraSlot | Upgrade::QuickHands | Upgrade::HyperFocus => Some(1),
+            Upgrade::SharperTools | Upgrade::BiggerBaskets => None,
+            Upgrade::LongerShifts => Some(MAX_LONGER_SHIFTS),
+        }
+    }
+
+    /// Coins for the next level. Repeatable upgrades get 50% more expensive each time.
+    pub fn cost(&self, level: i64) -> i64 {
+        let base_cost = match self {
+            Upgrade::ExtraSlot => 500.0,
+            Upgrade::QuickHands => 1000.0,
+            Upgrade::HyperFocus => 750.0,
+            Upgrade::SharperTools => 100.0,
+            Upgrade::BiggerBaskets => 150.0,
+            Upgrade::LongerShifts => 300.0,
+        };
+
+        (base_cost * 1.5f64.powi(level as i32)).round() as i64
+    }
+
+    pub fn get_modifiers(&self, level: i64) -> JobModifiers {
+        if level < 1 {
+            return JobModifiers::none();
+        }
+
+        match self {
+            Upgrade::ExtraSlot | Upgrade::LongerShifts => JobModifiers::none(),
+            Upgrade::QuickHands => JobModifiers { speed_multiplier: 1.25, ..JobModifiers::none() },
+            Upgrade::HyperFocus => JobModifiers { hyper_multiplier: 1.5, ..JobModifiers::none() },
+            Upgrade::SharperTools => JobModifiers { speed_multiplier: 1.0 + 0.05 * level as f64, ..JobModifiers::none() },
+            Upgrade::BiggerBaskets => JobModifiers { yield_multiplier: 1.0 + 0.05 * level as f64, ..JobModifiers::none() },
+        }
+    }
+}
+
+/// Levels of the upgrades bought so far.
+pub struct Shop {
+    pub upgrade_levels: HashMap<Upgrade, i64>,
+}
+
+impl Shop {
+    pub fn new() -> Self {
+        Self { upgrade_levels: HashMap::new() }
+    }
+
+    pub fn get_level(&self, upgrade: &Upgrade) -> i64 {
+        *self.upgrade_levels.get(upgrade).unwrap_or(&0)
+    }
+
+    pub fn is_maxed(&self, upgrade: &Upgrade) -> bool {
+        upgrade.max_level().is_some_and(|max_level| self.get_level(upgrade) >= max_level)
+    }
+
+    pub fn get_modifiers(&self) -> JobModifiers {
+        Upgrade::iter()
+            .map(|upgrade| upgrade.get_modifiers(self.get_level(&upgrade)))
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+}
+
+/// Buy price after the discounts friendly villagers give, rounded up. It never drops to what the market
+/// pays during a spike, see [`MAX_SPIKE_FACTOR`].
+pub fn discounted_buy_price(state: &GameState, item: &Item) -> Option<i64> {
+    let price = buy_price(item)? as f64 * (1.0 - state.villagers.shop_discount());
+    let lowest_price = base_price(item)? as f64 * MAX_SPIKE_FACTOR;
+
+    Some(price.max(lowest_price).ceil() as i64)
+}
+
+pub fn buy_item(state: &mut GameState, item: Item, amount: i64) -> bool {
+    let Some(price) = discounted_buy_price(state, &item) else {
+        return false;
+    };
+
+    let total = price * amount;
+
+    if amount < 1 || state.inventory.get_item_amount(&Item::Coin) < total {
+        return false;
+    }
+
+    state.inventory.add_item(Item::Coin, -total);
+    state.inventory.add_item(item, amount);
+
+    true
+}
+
+pub fn sell_item(state: &mut GameState, item: Item, amount: i64) -> bool {
+    if base_price(&item).is_none() || amount < 1 || state.inventory.get_item_amount(&item) < amount {
+        return false;
+    }
+
+    let coins = state.market.sell(&item, amount);
+
+    state.inventory.add_item(item, -amount);
+    state.inventory.add_item(Item::Coin, coins);
+
+    true
+}
+
+pub fn buy_upgrade(state: &mut GameState, upgrade: Upgrade) -> bool {
+    let level = state.shop.get_level(&upgrade);
+    let cost = upgrade.cost(level);
+
+    if state.shop.is_maxed(&upgrade) || state.inventory.get_item_amount(&Item::Coin) < cost {
+        return false;
+    }
+
+    if upgrade == Upgrade::ExtraSlot {
+        let slot_limit = state.research.slot_limit();
+        let Some(slot) = state.job_slots.iter_mut().find(|slot| matches!(slot.state, JobSlotState::Locked) && slot.index < slot_limit) else {
+            return false;
+        };
+
+        slot.set_state(JobSlotState::Empty);
+    }
+
+    state.inventory.add_item(Item::Coin, -cost);
+    state.shop.upgrade_levels.insert(upgrade, level + 1);
+
+    true
+}
+
+pub fn build_shop_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let row_height = 28.0;
+    let row_spacing = 8.0;
+    let column_width = (rect.w - padding * 4.0) / 3.0;
+    let coins = state.inventory.get_item_amount(&Item::Coin);
+
+    elements.push(UiElement::Image {
+        x: rect.x + rect.w - padding - 160.0,
+        y: rect.y + padding,
+        width: 32.0,
+        height: 32.0,
+        texture: BagOfCoins.get_texture(assets),
+        color: PaletteC::White.get_color(),
+    });
+
+    elements.push(UiElement::Text {
+        content: coins.to_string(),
+        font: assets.fonts.mono.clone(),
+        x: rect.x + rect.w - padding - 120.0,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    let columns = ["Materials", "Upgrades", "Sell"];
+
+    for (i, title) in columns.iter().enumerate() {
+        elements.push(UiElement::Text {
+            content: title.to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x: rect.x + padding + i as f32 * (column_width + padding),
+            y: rect.y + padding + 24.0,
+            font_size: 24.0,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    let top = rect.y + padding + 50.0;
+
+    // Materials
+    let x = rect.x + padding;
+
+    for (i, item) in SHOP_STOCK.iter().enumerate() {
+        let y = top + i as f32 * (row_height + row_spacing);
+        let price = discounted_buy_price(state, item).unwrap_or(0) * BUY_AMOUNT;
+
+        elements.extend(item_label(assets, *item, x, y, row_height));
+        elements.push(shop_button(assets, UiRect::new(x + column_width - 140.0, y, 140.0, row_height),
+            &format!("Buy {} for {}", BUY_AMOUNT, price), coins >= price, Intent::BuyItem(*item, BUY_AMOUNT)));
+    }
+
+    // Upgrades
+    let x = rect.x + padding * 2.0 + column_width;
+
+    for (i, upgrade) in Upgrade::iter().enumerate() {
+        let y = top + i as f32 * (row_height * 2.0 + row_spacing);
+        let level = state.shop.get_level(&upgrade);
+
+        let name = match upgrade.max_level() {
+            Some(_) => upgrade.get_name().to_string(),
+            None => format!("{} Lv. {}", upgrade.get_name(), level),
+        };
+
+        elements.push(UiElement::Text {
+            content: name,
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 16.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::Text {
+            content: upgrade.get_description().to_string(),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 36.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        if state.shop.is_maxed(&upgrade) {
+            elements.push(UiElement::Text {
+                content: "Owned".to_string(),
+                font: assets.fonts.text_bold.clone(),
+                x: x + column_width - 100.0,
+                y: y + 20.0,
+                font_size: 16.0,
+                color: palette::TEXT.get_color(),
+            });
+        } else {
+            let cost = upgrade.cost(level);
+            elements.push(shop_button(assets, UiRect::new(x + column_width - 100.0, y, 100.0, row_height),
+                &cost.to_string(), coins >= cost, Intent::BuyUpgrade(upgrade)));
+        }
+    }
+
+    // Sell whatever is in the inventory at the current market price
+    let x = rect.x + padding * 3.0 + column_width * 2.0;
+    let max_rows = ((rect.y + rect.h - padding - top) / (row_height + row_spacing)) as usize;
+    let sellable = Item::all().into_iter()
+        .filter(|item| base_price(item).is_some() && state.inventory.get_item_amount(item) > 0)
+        .take(max_rows);
+
+    for (i, item) in sellable.enumerate() {
+        let y = top + i as f32 * (row_height + row_spacing);
+        let amount = state.inventory.get_item_amount(&item);
+        let price = state.market.get_price(&item).unwrap_or(0.0);
+
+        elements.extend(item_label(assets, item, x, y, row_height));
+        elements.push(shop_button(assets, UiRect::new(x + column_width - 190.0, y, 90.0, row_height),
+            &format!("1 for {:.1}", price), true, Intent::SellItem(item, 1)));
+        elements.push(shop_button(assets, UiRect::new(x + column_width - 95.0, y, 95.0, row_height),
+            &format!("All {}", amount), true, Intent::SellItem(item, amount)));
+        elements.push(shop_button(assets, UiRect::new(x + column_width - 225.0, y, 30.0, row_height),
+            if state.market.is_spiking(&item) { "!" } else { "~" }, true, Intent::SelectMarketItem(item)));
+    }
+
+    // Price history of the selected item below the materials
+    if let Some(item) = state.market.chart_item {
+        let chart_y = top + SHOP_STOCK.len() as f32 * (row_height + row_spacing) + padding;
+        let chart_rect = UiRect::new(rect.x + padding, chart_y + 30.0, column_width, rect.y + rect.h - padding - chart_y - 30.0);
+        elements.extend(price_chart(state, assets, item, chart_rect));
+    }
+
+    elements
+}
+
+fn price_chart(state: &GameState, assets: &Assets, item: Item, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+    let history = state.market.prices.get(&item).map(|price| price.history.clone()).unwrap_or_default();
+    let highest = history.iter().copied().fold(base_price(&item).unwrap_or(1) as f64, f64::max);
+
+    elements.push(UiElement::Text {
+        content: format!("{} price, last {}", item.get_name(), pretty_duration(PRICE_HISTORY_LENGTH as f64 * MARKET_TICK_SECONDS)),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x,
+        y: rect.y - 10.0,
+        font_size: 16.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    elements.push(UiElement::Rectangle {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        color: palette::IMAGE_BACKGROUND.get_color(),
+        border_style: BorderStyle::Solid,
+    });
+
+    let bar_width = rect.w / PRICE_HISTORY_LENGTH as f32;
+
+    for (i, price) in history.iter().enumerate() {
+        let bar_height = (price / highest) as f32 * (rect.h - 8.0);
+
+        elements.push(UiElement::Rectangle {
+            x: rect.x + i as f32 * bar_width,
+            y: rect.y + rect.h - bar_height,
+            width: bar_width - 1.0,
+            height: bar_height,
+            color: palette::PROGRESS_COLOR.get_color(),
+            border_style: BorderStyle::None,
+        });
+    }
+
+    elements.push(UiElement::Text {
+        content: format!("{:.1}", highest),
+        font: assets.fonts.mono.clone(),
+        x: rect.x + 4.0,
+        y: rect.y + 16.0,
+        font_size: 14.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    elements
+}
+
+fn item_label(assets: &Assets, item: Item, x: f32, y: f32, size: f32) -> Vec<UiElement> {
+    vec![
+        UiElement::Image {
+            x,
+            y,
+            width: size,
+            height: size,
+            texture: item.get_texture(assets),
+            color: PaletteC::White.get_color(),
+        },
+        UiElement::Text {
+            content: item.get_name(),
+            font: assets.fonts.text.clone(),
+            x: x + size + 8.0,
+            y: y + size / 2.0 + 6.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        },
+    ]
+}
+
+fn shop_button(assets: &Assets, rectangle: UiRect, text: &str, affordable: bool, intent: Intent) -> UiElement {
+    UiElement::RectButton {
+        rectangle,
+        font_size: 14.0,
+        font: assets.fonts.text.clone(),
+        text: text.to_string(),
+        background_color: if affordable { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+        text_color: palette::BUTTON_TEXT.get_color(),
+        intent,
+        parent_clip: None,
+        border_style: BorderStyle::Solid,
+    }
+}
@@ -1,11 +1,11 @@
-use crate::assets::AssetId::{AlchemyAnim1, AlchemyAnim2, CookingAnim1, CookingAnim2, CookingIcon, HerbalismAnim1, HerbalismAnim2, HerbalismIcon, HuntingAnim1, HuntingAnim2, HuntingIcon, LumberingIcon, MiningAnim1, MiningAnim2, MiningIcon, SmithingAnim1, SmithingAnim2, WoodAnim1, WoodAnim2};
+use crate::assets::AssetId::{AlchemyAnim1, AlchemyAnim2, CookingAnim1, CookingAnim2, CookingIcon, HerbalismAnim1, HerbalismAnim2, HerbalismIcon, HuntingAnim1, HuntingAnim2, HuntingIcon, LumberingIcon, MiningAnim1, MiningAnim2, MiningIcon, SmithingAnim1, SmithingAnim2, WoodAnim1, WoodAnim2, WoodworkingAnim1, WoodworkingAnim2, WoodworkingIcon};
 use crate::assets::Assets;
 use crate::counts_actions::CountsActions;
-use crate::job::{AlchemyJobArchetype, CookingJobArchetype, ForagingJobArchetype, HuntingJobArchetype, JobArchetype, LumberingJobArchetype, MiningJobArchetype, SmithingJobArchetype};
+use crate::job::{AlchemyJobArchetype, CookingJobArchetype, ForagingJobArchetype, HuntingJobArchetype, JobArchetype, LumberingJobArchetype, MiningJobArchetype, SmithingJobArchetype, WoodworkingJobArchetype};
 use macroquad::prelude::Texture2D;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
-use SkillArchetype::{Alchemy, Cooking, Foraging, Hunting, Lumbering, Mining, Smithing};
+use SkillArchetype::{Alchemy, Cooking, Foraging, Hunting, Lumbering, Mining, Smithing, Woodworking};
 
 #[derive(EnumIter, Clone, Debug)]
 pub enum SkillCategory {
@@ -33,6 +33,7 @@ impl SkillCategory {
                 Smithing,
                 Alchemy,
                 Cooking,
+                Woodworking,
             ],
         }
     }
@@ -50,6 +51,7 @@ pub enum SkillArchetype {
     Smithing,
     Alchemy,
     Cooking,
+    Woodworking,
 }
 
 impl SkillArchetype {
@@ -62,6 +64,7 @@ impl SkillArchetype {
             Smithing => "Smithing",
             Alchemy => "Alchemy",
             Cooking => "Cooking",
+            Woodworking => "Woodworking",
         }
     }
 
@@ -69,6 +72,11 @@ impl SkillArchetype {
         match self {
             Alchemy => vec![
                 JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion),
+                JobArchetype::Alchemy(AlchemyJobArchetype::BlankRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::TimeRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::HeartRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::GreaterTimeRune),
+                JobArchetype::Alchemy(AlchemyJobArchetype::GreaterHeartRune),
             ],
 
             Cooking => vec![
@@ -78,6 +86,7 @@ impl SkillArchetype {
             Lumbering => vec![
                 JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
                 JobArchetype::Lumbering(LumberingJobArchetype::Graintree),
+                JobArchetype::Lumbering(LumberingJobArchetype::Kindlewood),
             ],
 
             Mining => vec![
@@ -94,6 +103,17 @@ impl SkillArchetype {
 
             Smithing => vec![
                 JobArchetype::Smithing(SmithingJobArchetype::IronBar),
+                JobArchetype::Smithing(SmithingJobArchetype::Axe),
+                JobArchetype::Smithing(SmithingJobArchetype::Pickaxe),
+                JobArchetype::Smithing(SmithingJobArchetype::Knife),
+                JobArchetype::Smithing(SmithingJobArchetype::Mortar),
+            ],
+
+            Woodworking => vec![
+                JobArchetype::Woodworking(WoodworkingJobArchetype::Plank),
+                JobArchetype::Woodworking(WoodworkingJobArchetype::GrainPlank),
+                JobArchetype::Woodworking(WoodworkingJobArchetype::Beam),
+                JobArchetype::Woodworking(WoodworkingJobArchetype::Crate),
             ],
         }
     }
@@ -107,6 +127,7 @@ impl SkillArchetype {
             Smithing => (SmithingAnim1.get_texture(assets), SmithingAnim2.get_texture(assets)),
             Cooking => (CookingAnim1.get_texture(assets), CookingAnim2.get_texture(assets)),
             Alchemy => (AlchemyAnim1.get_texture(assets), AlchemyAnim2.get_texture(assets)),
+            Woodworking => (WoodworkingAnim1.get_texture(assets), WoodworkingAnim2.get_texture(assets)),
         }
     }
 
@@ -117,6 +138,7 @@ impl SkillArchetype {
             Hunting => HuntingIcon.get_texture(assets),
             Foraging => HerbalismIcon.get_texture(assets),
             Cooking => CookingIcon.get_texture(assets),
+            Woodworking => WoodworkingIcon.get_texture(assets),
             _default => Texture2D::empty(),
         }
     }
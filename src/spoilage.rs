@@ -0,0 +1,123 @@
+use crate::game::{Inventory, Item};
+use std::collections::{HashMap, VecDeque};
+
+/// Food ages a bucket at a time, so the cost of a long time skip doesn't depend on how much food there is.
+pub const SPOIL_BUCKET_SECONDS: f64 = 5.0 * 60.0;
+
+/// Every cellar level makes food keep this much longer.
+pub const CELLAR_SHELF_LIFE_BONUS: f64 = 0.5;
+
+pub const PERISHABLES: [Item; 3] = [Item::Meat, Item::Herb, Item::Sandwich];
+
+/// Seconds an item keeps before it spoils. Cooking meat into sandwiches keeps it much longer.
+pub fn shelf_life(item: &Item) -> Option<f64> {
+    match item {
+        Item::Meat => Some(30.0 * 60.0),
+        Item::Herb => Some(60.0 * 60.0),
+        Item::Sandwich => Some(4.0 * 60.0 * 60.0),
+        _ => None,
+    }
+}
+
+fn shelf_life_buckets(item: &Item) -> usize {
+    shelf_life(item).map(|seconds| (seconds / SPOIL_BUCKET_SECONDS).ceil() as usize).unwrap_or(0)
+}
+
+/// Perishable items by age, newest bucket first. The buckets follow the inventory:
+/// new items land in the newest bucket and items taken out leave from the oldest.
+pub struct Spoilage {
+    pub batches: HashMap<Item, VecDeque<i64>>,
+    pub age_accumulator: f64,
+}
+
+impl Spoilage {
+    pub fn new() -> Self {
+        Self {
+            batches: HashMap::new(),
+            age_accumulator: 0.0,
+        }
+    }
+
+    pub fn tracked_amount(&self, item: &Item) -> i64 {
+        self.batches.get(item).map(|buckets| buckets.iter().sum()).unwrap_or(0)
+    }
+
+    /// Items that go off when the buckets age next.
+    pub fn spoiling_soon(&self, item: &Item) -> i64 {
+        match self.batches.get(item) {
+            Some(buckets) if buckets.len() >= shelf_life_buckets(item) => *buckets.back().unwrap_or(&0),
+            _ => 0,
+        }
+    }
+
+    /// Seconds until the buckets age next, at the given aging rate.
+    pub fn seconds_until_aging(&self, aging_rate: f64) -> f64 {
+        (SPOIL_BUCKET_SECONDS - self.age_accumulator) / aging_rate
+    }
+
+    /// Brings the buckets in line with what the inventory holds. Only the net change since the last
+    /// sync shows, which is why it runs every tick.
+    pub fn sync(&mut self, inventory: &Inventory) {
+        for item in PERISHABLES {
+            let held = inventory.get_item_amount(&item).max(0);
+            let tracked = self.tracked_amount(&item);
+            let buckets = self.batches.entry(item).or_default();
+
+            if held > tracked {
+                match buckets.front_mut() {
+                    Some(newest) => *newest += held - tracked,
+                    None => buckets.push_front(held - tracked),
+                }
+            }
+
+            let mut taken = tracked - held;
+            while taken > 0 {
+                let Some(oldest) = buckets.back_mut() else { break };
+                let amount = taken.min(*oldest);
+                *oldest -= amount;
+                taken -= amount;
+
+                if *oldest == 0 {
+                    buckets.pop_back();
+                }
+            }
+        }
+    }
+
+    /// Ages the food and takes what spoiled out of the inventory. Returns the spoiled amounts.
+    pub fn update(&mut self, inventory: &mut Inventory, aging_rate: f64, dt: f32) -> Vec<(Item, i64)> {
+        self.sync(inventory);
+        self.age_accumulator += dt as f64 * aging_rate;
+
+        let mut spoiled: HashMap<Item, i64> = HashMap::new();
+
+        while self.age_accumulator >= SPOIL_BUCKET_SECONDS {
+            self.age_accumulator -= SPOIL_BUCKET_SECONDS;
+
+            for (item, buckets) in &mut self.batches {
+                buckets.push_front(0);
+
+                while buckets.len() > shelf_life_buckets(item) {
+                    *spoiled.entry(*item).or_insert(0) += buckets.pop_back().unwrap_or(0);
+                }
+            }
+        }
+
+        let spoiled: Vec<(Item, i64)> = PERISHABLES.into_iter()
+            .map(|item| (item, *spoiled.get(&item).unwrap_or(&0)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect();
+
+        for (item, amount) in &spoiled {
+            let lost = (*amount).min(inventory.get_item_amount(item));
+            inventory.add_item(*item, -lost);
+        }
+
+        spoiled
+    }
+}
+
+/// How fast food ages with the given cellar level, 1.0 without a cellar.
+pub fn aging_rate(cellar_level: i64) -> f64 {
+    1.0 / (1.0 + CELLAR_SHELF_LIFE_BONUS * cellar_level as f64)
+}
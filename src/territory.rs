@@ -0,0 +1,327 @@
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{GameState, Intent, Item, UiRect, WoodItem, WoodWorkingItem};
+use crate::job::{ForagingJobArchetype, HuntingJobArchetype, JobArchetype, JobModifiers, LumberingJobArchetype, MiningJobArchetype};
+use crate::palette;
+use crate::palette::PaletteC;
+use macroquad::color::Color;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// The map, one character per tile: F forest, G grain forest, M mine, H hunting grounds,
+/// E herb meadow and V the village everything grows from.
+pub const MAP_LAYOUT: [&str; 5] = [
+    "GFFMMMH",
+    "FFFMMHH",
+    "GFFVHHE",
+    "FFEEHEE",
+    "GFEEEEM",
+];
+
+/// Every tile of a kind adds this much yield to its jobs, past the one a job needs to run at all.
+pub const TILE_YIELD_BONUS: f64 = 0.1;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum TileKind {
+    Village,
+    Forest,
+    GrainForest,
+    Mine,
+    HuntingGrounds,
+    HerbMeadow,
+}
+
+impl TileKind {
+    pub fn from_char(c: char) -> Option<TileKind> {
+        match c {
+            'V' => Some(TileKind::Village),
+            'F' => Some(TileKind::Forest),
+            'G' => Some(TileKind::GrainForest),
+            'M' => Some(TileKind::Mine),
+            'H' => Some(TileKind::HuntingGrounds),
+            'E' => Some(TileKind::HerbMeadow),
+            _ => None,
+        }
+    }
+
+    pub fn get_name(&self) -> &str {
+        match self {
+            TileKind::Village => "Village",
+            TileKind::Forest => "Forest",
+            TileKind::GrainForest => "Grain Forest",
+            TileKind::Mine => "Mine",
+            TileKind::HuntingGrounds => "Hunting Grounds",
+            TileKind::HerbMeadow => "Herb Meadow",
+        }
+    }
+
+    pub fn get_color(&self) -> Color {
+        match self {
+            TileKind::Village => PaletteC::Peach.get_color(),
+            TileKind::Forest => PaletteC::Grass.get_color(),
+            TileKind::GrainForest => PaletteC::WarmYellow.get_color(),
+            TileKind::Mine => PaletteC::DarkGray.get_color(),
+            TileKind::HuntingGrounds => PaletteC::Mocha.get_color(),
+            TileKind::HerbMeadow => PaletteC::Aqua.get_color(),
+        }
+    }
+
+    /// Gathering jobs the tile boosts.
+    pub fn get_jobs(&self) -> Vec<JobArchetype> {
+        match self {
+            TileKind::Village => vec![],
+            TileKind::Forest => vec![
+                JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+                JobArchetype::Lumbering(LumberingJobArchetype::Kindlewood),
+            ],
+            TileKind::GrainForest => vec![JobArchetype::Lumbering(LumberingJobArchetype::Graintree)],
+            TileKind::Mine => vec![JobArchetype::Mining(MiningJobArchetype::Iron)],
+            TileKind::HuntingGrounds => vec![JobArchetype::Hunting(HuntingJobArchetype::Deer)],
+            TileKind::HerbMeadow => vec![JobArchetype::Foraging(ForagingJobArchetype::Herb)],
+        }
+    }
+
+    /// Whether the jobs of this tile can't run before one is claimed.
+    pub fn is_required(&self) -> bool {
+        *self == TileKind::GrainForest
+    }
+
+    /// Price of a tile once the given number of tiles is claimed. Every claim makes the next one 30% dearer.
+    pub fn cost(&self, claimed_count: usize) -> Vec<(Item, i64)> {
+        let base_cost = match self {
+            TileKind::Village => vec![],
+            TileKind::Forest => vec![(Item::Coin, 30)],
+            TileKind::GrainForest => vec![(Item::Coin, 60), (Item::Woodworking(WoodWorkingItem::Plank), 5)],
+            TileKind::Mine => vec![(Item::Coin, 80), (Item::Wood(WoodItem::Craftwood), 20)],
+            TileKind::HuntingGrounds => vec![(Item::Coin, 50)],
+            TileKind::HerbMeadow => vec![(Item::Coin, 40)],
+        };
+
+        base_cost.into_iter()
+            .map(|(item, amount)| (item, (amount as f64 * 1.3f64.powi(claimed_count as i32)).round() as i64))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Tile {
+    pub kind: TileKind,
+    pub col: usize,
+    pub row: usize,
+}
+
+pub fn map_tiles() -> Vec<Tile> {
+    MAP_LAYOUT.iter().enumerate()
+        .flat_map(|(row, line)| line.chars().enumerate()
+            .filter_map(move |(col, c)| TileKind::from_char(c).map(|kind| Tile { kind, col, row })))
+        .collect()
+}
+
+/// Tiles the player holds, by position. The village is always theirs.
+pub struct Territory {
+    pub tiles: Vec<Tile>,
+    pub claimed: Vec<(usize, usize)>,
+}
+
+impl Territory {
+    pub fn new() -> Self {
+        let tiles = map_tiles();
+        let claimed = tiles.iter()
+            .filter(|tile| tile.kind == TileKind::Village)
+            .map(|tile| (tile.col, tile.row))
+            .collect();
+
+        Self { tiles, claimed }
+    }
+
+    pub fn get_tile(&self, col: usize, row: usize) -> Option<&Tile> {
+        self.tiles.iter().find(|tile| tile.col == col && tile.row == row)
+    }
+
+    pub fn is_claimed(&self, col: usize, row: usize) -> bool {
+        self.claimed.contains(&(col, row))
+    }
+
+    /// Unclaimed tiles next to one that is already claimed.
+    pub fn is_claimable(&self, col: usize, row: usize) -> bool {
+        !self.is_claimed(col, row) && self.claimed.iter().any(|(claimed_col, claimed_row)| {
+            claimed_col.abs_diff(col) + claimed_row.abs_diff(row) == 1
+        })
+    }
+
+    pub fn count(&self, kind: &TileKind) -> usize {
+        self.tiles.iter()
+            .filter(|tile| tile.kind == *kind && self.is_claimed(tile.col, tile.row))
+            .count()
+    }
+
+    /// Tiles claimed so far, the village not included.
+    pub fn claimed_count(&self) -> usize {
+        self.claimed.len() - self.count(&TileKind::Village)
+    }
+
+    pub fn is_job_available(&self, job_archetype: &JobArchetype) -> bool {
+        TileKind::iter()
+            .filter(|kind| kind.is_required() && kind.get_jobs().contains(job_archetype))
+            .all(|kind| self.count(&kind) > 0)
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        TileKind::iter()
+            .filter(|kind| kind.get_jobs().contains(job_archetype))
+            .map(|kind| {
+                let bonus_tiles = self.count(&kind).saturating_sub(if kind.is_required() { 1 } else { 0 });
+                JobModifiers { yield_multiplier: 1.0 + TILE_YIELD_BONUS * bonus_tiles as f64, ..JobModifiers::none() }
+            })
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+}
+
+pub fn can_claim(state: &GameState, col: usize, row: usize) -> bool {
+    let territory = &state.territory;
+
+    territory.get_tile(col, row).is_some_and(|tile| {
+        territory.is_claimable(col, row)
+            && tile.kind.cost(territory.claimed_count()).iter().all(|(item, amount)| state.inventory.get_item_amount(item) >= *amount)
+    })
+}
+
+pub fn claim_tile(state: &mut GameState, col: usize, row: usize) -> bool {
+    if !can_claim(state, col, row) {
+        return false;
+    }
+
+    let Some(tile) = state.territory.get_tile(col, row).copied() else {
+        return false;
+    };
+
+    for (item, amount) in tile.kind.cost(state.territory.claimed_count()) {
+        state.inventory.add_item(item, -amount);
+    }
+
+    state.territory.claimed.push((col, row));
+
+    true
+}
+
+pub fn build_territory_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let tile_size = 92.0;
+    let tile_spacing = 4.0;
+    let map_x = rect.x + padding;
+    let map_y = rect.y + padding + 44.0;
+    let territory = &state.territory;
+
+    elements.push(UiElement::Text {
+        content: format!("Territory - {} tiles claimed", territory.claimed_count()),
+        font: assets.fonts.text_bold.clone(),
+        x: map_x,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    for tile in &territory.tiles {
+        let x = map_x + tile.col as f32 * (tile_size + tile_spacing);
+        let y = map_y + tile.row as f32 * (tile_size + tile_spacing);
+        let claimed = territory.is_claimed(tile.col, tile.row);
+
+        elements.push(UiElement::Rectangle {
+            x,
+            y,
+            width: tile_size,
+            height: tile_size,
+            color: if claimed { tile.kind.get_color() } else { Color { a: 0.3, ..tile.kind.get_color() } },
+            border_style: if claimed { BorderStyle::Solid } else { BorderStyle::Dotted },
+        });
+
+        elements.push(UiElement::Text {
+            content: tile.kind.get_name().to_string(),
+            font: assets.fonts.text_bold.clone(),
+            x: x + 6.0,
+            y: y + 18.0,
+            font_size: 12.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        if territory.is_claimable(tile.col, tile.row) {
+            elements.push(UiElement::RectButton {
+                rectangle: UiRect::new(x + 6.0, y + tile_size - 30.0, tile_size - 12.0, 24.0),
+                font_size: 12.0,
+                font: assets.fonts.text_bold.clone(),
+                text: "Claim".to_string(),
+                background_color: if can_claim(state, tile.col, tile.row) { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+                text_color: palette::BUTTON_TEXT.get_color(),
+                intent: Intent::ClaimTile(tile.col, tile.row),
+                parent_clip: None,
+                border_style: BorderStyle::Solid,
+            });
+        }
+    }
+
+    // what each kind of tile costs next and what the claimed ones give
+    let columns = MAP_LAYOUT[0].len() as f32;
+    let legend_x = map_x + columns * (tile_size + tile_spacing) + padding;
+
+    for (i, kind) in TileKind::iter().filter(|kind| *kind != TileKind::Village).enumerate() {
+        let y = map_y + i as f32 * 72.0;
+        let count = territory.count(&kind);
+        let bonus_tiles = count.saturating_sub(if kind.is_required() { 1 } else { 0 });
+
+        let cost = kind.cost(territory.claimed_count()).iter()
+            .map(|(item, amount)| format!("{} {}", amount, item.get_name()))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        let effect = if kind.is_required() && count == 0 {
+            "needed for its jobs".to_string()
+        } else {
+            format!("+{:.0}% yield", bonus_tiles as f64 * TILE_YIELD_BONUS * 100.0)
+        };
+
+        let jobs = kind.get_jobs().iter()
+            .map(|job| job.get_name())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        elements.push(UiElement::Rectangle {
+            x: legend_x,
+            y,
+            width: 16.0,
+            height: 16.0,
+            color: kind.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::Text {
+            content: format!("{} x{} - {} {}", kind.get_name(), count, jobs, effect),
+            font: assets.fonts.text_bold.clone(),
+            x: legend_x + 24.0,
+            y: y + 14.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::Text {
+            content: format!("Next: {}", cost),
+            font: assets.fonts.text.clone(),
+            x: legend_x + 24.0,
+            y: y + 36.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+    }
+
+    elements
+}
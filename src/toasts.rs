@@ -0,0 +1,72 @@
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::GameState;
+use crate::palette;
+use crate::palette::PaletteC;
+use macroquad::prelude::Vec2;
+
+pub const TOAST_SECONDS: f64 = 5.0;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Toast {
+    pub text: String,
+    pub remaining_seconds: f64,
+}
+
+/// Short notifications in the corner of the screen, from any part of the game.
+pub struct Toasts {
+    pub active: Vec<Toast>,
+}
+
+impl Toasts {
+    pub fn new() -> Self {
+        Self { active: vec![] }
+    }
+
+    pub fn push(&mut self, toast: Toast) {
+        self.active.push(toast);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.active.is_empty()
+    }
+
+    pub fn update(&mut self, dt: f32) {
+        for toast in &mut self.active {
+            toast.remaining_seconds -= dt as f64;
+        }
+
+        self.active.retain(|toast| toast.remaining_seconds > 0.0);
+    }
+}
+
+pub fn build_toasts(state: &GameState, assets: &Assets, offset: Vec2) -> Vec<UiElement> {
+    let mut elements = vec![];
+    let width = 320.0;
+    let height = 40.0;
+
+    for (i, toast) in state.toasts.active.iter().enumerate() {
+        let x = offset.x + 1280.0 - width - 16.0;
+        let y = offset.y + 720.0 - 16.0 - (i as f32 + 1.0) * (height + 8.0);
+
+        elements.push(UiElement::Rectangle {
+            x,
+            y,
+            width,
+            height,
+            color: PaletteC::Peach.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        elements.push(UiElement::Text {
+            content: toast.text.clone(),
+            font: assets.fonts.text_bold.clone(),
+            x: x + 12.0,
+            y: y + height / 2.0 + 6.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+    }
+
+    elements
+}
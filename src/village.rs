@@ -0,0 +1,254 @@
+use crate::assets::AssetId::{Hut1, Hut2, ParchmentFrame};
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{GameState, Intent, Item, UiRect, WoodItem, WoodWorkingItem};
+use crate::job::{JobArchetype, JobModifiers};
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::skill::SkillArchetype;
+use macroquad::color::Color;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+pub const MAX_BUILDING_LEVEL: i64 = 5;
+
+/// Items of one kind the player can hold before any warehouse is built. Coins are never capped.
+pub const BASE_STORAGE: i64 = 2_000;
+pub const STORAGE_PER_WAREHOUSE_LEVEL: i64 = 1_000;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Building {
+    LumberYard,
+    Smithy,
+    Kitchen,
+    Warehouse,
+    Cellar,
+}
+
+impl Building {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Building::LumberYard => "Lumber Yard",
+            Building::Smithy => "Smithy",
+            Building::Kitchen => "Kitchen",
+            Building::Warehouse => "Warehouse",
+            Building::Cellar => "Cellar",
+        }
+    }
+
+    pub fn get_description(&self) -> &str {
+        match self {
+            Building::LumberYard => "+10% Lumbering and Woodworking speed per level",
+            Building::Smithy => "+10% Mining and Smithing speed per level",
+            Building::Kitchen => "+10% Hunting and Cooking yield per level",
+            Building::Warehouse => "Room for 1000 more of every item per level",
+            Building::Cellar => "Meat, herbs and sandwiches keep 50% longer per level",
+        }
+    }
+
+    /// Skills that get the bonus of this building.
+    pub fn get_skills(&self) -> Vec<SkillArchetype> {
+        match self {
+            Building::LumberYard => vec![SkillArchetype::Lumbering, SkillArchetype::Woodworking],
+            Building::Smithy => vec![SkillArchetype::Mining, SkillArchetype::Smithing],
+            Building::Kitchen => vec![SkillArchetype::Hunting, SkillArchetype::Cooking],
+            Building::Warehouse | Building::Cellar => vec![],
+        }
+    }
+
+    /// Materials for the next level. Every level costs 60% more than the one before.
+    pub fn cost(&self, level: i64) -> Vec<(Item, i64)> {
+        let plank = Item::Woodworking(WoodWorkingItem::Plank);
+
+        let base_cost = match self {
+            Building::LumberYard => vec![(plank, 20), (Item::Coin, 50)],
+            Building::Smithy => vec![(plank, 30), (Item::IronBar, 10), (Item::Coin, 100)],
+            Building::Kitchen => vec![(plank, 25), (Item::IronBar, 5), (Item::Coin, 75)],
+            Building::Warehouse => vec![(plank, 40), (Item::IronBar, 20), (Item::Coin, 150)],
+            Building::Cellar => vec![(plank, 30), (Item::Wood(WoodItem::Craftwood), 40), (Item::Coin, 80)],
+        };
+
+        base_cost.into_iter()
+            .map(|(item, amount)| (item, (amount as f64 * 1.6f64.powi(level as i32)).round() as i64))
+            .collect()
+    }
+
+    pub fn get_modifiers(&self, level: i64, job_archetype: &JobArchetype) -> JobModifiers {
+        if level < 1 || !self.get_skills().contains(&job_archetype.get_skill_type()) {
+            return JobModifiers::none();
+        }
+
+        let bonus = 1.0 + 0.1 * level as f64;
+
+        match self {
+            Building::Kitchen => JobModifiers { yield_multiplier: bonus, ..JobModifiers::none() },
+            _ => JobModifiers { speed_multiplier: bonus, ..JobModifiers::none() },
+        }
+    }
+}
+
+/// Levels of the buildings constructed so far. A level of 0 means the plot is empty.
+pub struct Village {
+    pub building_levels: HashMap<Building, i64>,
+}
+
+impl Village {
+    pub fn new() -> Self {
+        Self {
+            building_levels: HashMap::new(),
+        }
+    }
+
+    pub fn get_level(&self, building: &Building) -> i64 {
+        *self.building_levels.get(building).unwrap_or(&0)
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        Building::iter()
+            .map(|building| building.get_modifiers(self.get_level(&building), job_archetype))
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+
+    pub fn storage_capacity(&self) -> i64 {
+        BASE_STORAGE + STORAGE_PER_WAREHOUSE_LEVEL * self.get_level(&Building::Warehouse)
+    }
+
+    /// How many more of the item fit into storage.
+    pub fn storage_room(&self, item: &Item, amount_held: i64) -> i64 {
+        if *item == Item::Coin {
+            return i64::MAX;
+        }
+
+        (self.storage_capacity() - amount_held).max(0)
+    }
+}
+
+pub fn can_construct(state: &GameState, building: &Building) -> bool {
+    let level = state.village.get_level(building);
+
+    level < MAX_BUILDING_LEVEL
+        && building.cost(level).iter().all(|(item, amount)| state.inventory.get_item_amount(item) >= *amount)
+}
+
+/// Builds the first level of the building or upgrades it to the next one.
+pub fn construct_building(state: &mut GameState, building: Building) -> bool {
+    if !can_construct(state, &building) {
+        return false;
+    }
+
+    let level = state.village.get_level(&building);
+
+    for (item, amount) in building.cost(level) {
+        state.inventory.add_item(item, -amount);
+    }
+
+    state.village.building_levels.insert(building, level + 1);
+
+    true
+}
+
+pub fn build_village_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let spacing = 16.0;
+    let building_count = Building::iter().count() as f32;
+    let plot_width = (rect.w - padding * 2.0 - spacing * (building_count - 1.0)) / building_count;
+    let plot_height = 260.0;
+
+    elements.push(UiElement::Text {
+        content: format!("Village - storage for {} of each item", state.village.storage_capacity()),
+        font: assets.fonts.text_bold.clone(),
+        x: rect.x + padding,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    for (i, building) in Building::iter().enumerate() {
+        let x = rect.x + padding + i as f32 * (plot_width + spacing);
+        let y = rect.y + padding + 50.0;
+        let level = state.village.get_level(&building);
+
+        elements.push(UiElement::Rectangle {
+            x,
+            y,
+            width: plot_width,
+            height: plot_height,
+            color: palette::IMAGE_BACKGROUND.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        // the small hut grows into the big one, empty plots show a faded outline
+        let hut_size = if level >= 3 { 200.0 } else { 160.0 };
+        let hut_texture = if level >= 3 { Hut2.get_texture(assets) } else { Hut1.get_texture(assets) };
+        let hut_color = if level > 0 { PaletteC::White.get_color() } else { Color { a: 0.25, ..PaletteC::White.get_color() } };
+
+        elements.push(UiElement::Image {
+            x: x + plot_width / 2.0 - hut_size / 2.0,
+            y: y + plot_height - hut_size - 8.0,
+            width: hut_size,
+            height: hut_size,
+            texture: hut_texture,
+            color: hut_color,
+        });
+
+        elements.push(UiElement::Text {
+            content: if level > 0 { format!("{} Lv. {}", building.get_name(), level) } else { building.get_name().to_string() },
+            font: assets.fonts.text_bold.clone(),
+            x: x + 12.0,
+            y: y + 24.0,
+            font_size: 18.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        elements.push(UiElement::Text {
+            content: building.get_description().to_string(),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + plot_height + 24.0,
+            font_size: 12.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        if level >= MAX_BUILDING_LEVEL {
+            continue;
+        }
+
+        for (j, (item, amount)) in building.cost(level).iter().enumerate() {
+            let have = state.inventory.get_item_amount(item);
+
+            elements.push(UiElement::Text {
+                content: format!("{} {}", amount, item.get_name()),
+                font: assets.fonts.text.clone(),
+                x,
+                y: y + plot_height + 48.0 + j as f32 * 20.0,
+                font_size: 14.0,
+                color: if have >= *amount { palette::TEXT.get_color() } else { PaletteC::Coral.get_color() },
+            });
+        }
+
+        elements.push(UiElement::RectButton {
+            rectangle: UiRect::new(x, y + plot_height + 120.0, plot_width, 32.0),
+            font_size: 14.0,
+            font: assets.fonts.text_bold.clone(),
+            text: if level > 0 { "Upgrade".to_string() } else { "Build".to_string() },
+            background_color: if can_construct(state, &building) { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+            text_color: palette::BUTTON_TEXT.get_color(),
+            intent: Intent::ConstructBuilding(building),
+            parent_clip: None,
+            border_style: BorderStyle::Solid,
+        });
+    }
+
+    elements
+}
@@ -0,0 +1,351 @@
+use crate::toasts::{Toast, TOAST_SECONDS};
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::draw::{BorderStyle, UiElement};
+use crate::game::{GameState, Intent, Item, UiRect, WoodWorkingItem};
+use crate::job::{JobArchetype, JobModifiers};
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::research::ResearchNode;
+use crate::runes::describe_modifiers;
+use crate::skill::SkillArchetype;
+use std::collections::HashMap;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+/// Affection needed for each level, starting at level 1.
+pub const AFFECTION_LEVELS: [i64; 3] = [25, 75, 200];
+
+pub const LOVED_GIFT_AFFECTION: i64 = 10;
+pub const LIKED_GIFT_AFFECTION: i64 = 4;
+pub const OTHER_GIFT_AFFECTION: i64 = 1;
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Villager {
+    Mara,
+    Oswin,
+    Wren,
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Preference {
+    Loved,
+    Liked,
+    Neutral,
+}
+
+/// What a villager does for the player once they like them enough.
+#[derive(Clone, PartialEq, Debug)]
+pub enum VillagerPerk {
+    ShopDiscount(f64),
+    SkillBonus(SkillArchetype, JobModifiers),
+    Teaches(ResearchNode),
+}
+
+impl VillagerPerk {
+    pub fn get_description(&self) -> String {
+        match self {
+            VillagerPerk::ShopDiscount(discount) => format!("{:.0}% off in the shop", discount * 100.0),
+            VillagerPerk::SkillBonus(skill, modifiers) => format!("{} {}", describe_modifiers(modifiers), skill.get_name()),
+            VillagerPerk::Teaches(node) => format!("Teaches {}", node.get_name()),
+        }
+    }
+}
+
+impl Villager {
+    pub fn get_name(&self) -> &str {
+        match self {
+            Villager::Mara => "Mara",
+            Villager::Oswin => "Oswin",
+            Villager::Wren => "Wren",
+        }
+    }
+
+    pub fn get_title(&self) -> &str {
+        match self {
+            Villager::Mara => "the Baker",
+            Villager::Oswin => "the Smith",
+            Villager::Wren => "the Herbalist",
+        }
+    }
+
+    pub fn get_preference(&self, item: &Item) -> Preference {
+        let (loved, liked) = match self {
+            Villager::Mara => (vec![Item::Sandwich], vec![Item::Meat, Item::Berry]),
+            Villager::Oswin => (vec![Item::Woodworking(WoodWorkingItem::Beam)], vec![Item::IronBar, Item::Sandwich]),
+            Villager::Wren => (vec![Item::ManaPotion], vec![Item::Herb, Item::Berry]),
+        };
+
+        if loved.contains(item) {
+            Preference::Loved
+        } else if liked.contains(item) {
+            Preference::Liked
+        } else {
+            Preference::Neutral
+        }
+    }
+
+    /// Items the villager is glad to get, the loved ones first.
+    pub fn get_favorite_items(&self) -> Vec<Item> {
+        let mut items: Vec<Item> = Item::all().into_iter()
+            .filter(|item| self.get_preference(item) != Preference::Neutral)
+            .collect();
+
+        items.sort_by_key(|item| self.get_preference(item) != Preference::Loved);
+        items
+    }
+
+    /// Perks for reaching levels 1, 2 and 3.
+    pub fn get_perks(&self) -> [VillagerPerk; 3] {
+        match self {
+            Villager::Mara => [
+                VillagerPerk::ShopDiscount(0.1),
+                VillagerPerk::SkillBonus(SkillArchetype::Cooking, JobModifiers { yield_multiplier: 1.2, ..JobModifiers::none() }),
+                VillagerPerk::ShopDiscount(0.1),
+            ],
+            Villager::Oswin => [
+                VillagerPerk::SkillBonus(SkillArchetype::Mining, JobModifiers { speed_multiplier: 1.15, ..JobModifiers::none() }),
+                VillagerPerk::Teaches(ResearchNode::Toolmaking),
+                VillagerPerk::SkillBonus(SkillArchetype::Smithing, JobModifiers { speed_multiplier: 1.2, ..JobModifiers::none() }),
+            ],
+            Villager::Wren => [
+                VillagerPerk::SkillBonus(SkillArchetype::Foraging, JobModifiers { speed_multiplier: 1.15, ..JobModifiers::none() }),
+                VillagerPerk::SkillBonus(SkillArchetype::Alchemy, JobModifiers { hyper_multiplier: 1.25, ..JobModifiers::none() }),
+                VillagerPerk::Teaches(ResearchNode::Runecraft),
+            ],
+        }
+    }
+
+    /// What the villager says at each level, from stranger to close friend.
+    pub fn get_dialog(&self, level: usize) -> &str {
+        let lines = match self {
+            Villager::Mara => [
+                "Bread's not free, you know.",
+                "Oh, it's you again. Sit, I'll find you a crust.",
+                "You smell of sawdust. Here, take the warm loaf.",
+                "The ovens feel colder on days you don't drop by.",
+            ],
+            Villager::Oswin => [
+                "Mind the sparks.",
+                "Decent beam, that. You've got an eye.",
+                "Hold the tongs like this. No, like this.",
+                "Every blade I forge now, I wonder if you'd like it.",
+            ],
+            Villager::Wren => [
+                "Don't step on the chamomile.",
+                "You brought herbs? How thoughtful.",
+                "The runes hum when you're around. Strange.",
+                "Stay a while. The meadow is quieter with you here.",
+            ],
+        };
+
+        lines[level.min(lines.len() - 1)]
+    }
+}
+
+pub fn affection_level_name(level: usize) -> &'static str {
+    ["Stranger", "Acquaintance", "Friend", "Close Friend"][level.min(AFFECTION_LEVELS.len())]
+}
+
+/// Affection of every villager towards the player.
+pub struct Villagers {
+    pub affection: HashMap<Villager, i64>,
+}
+
+impl Villagers {
+    pub fn new() -> Self {
+        Self {
+            affection: HashMap::new(),
+        }
+    }
+
+    pub fn get_affection(&self, villager: &Villager) -> i64 {
+        *self.affection.get(villager).unwrap_or(&0)
+    }
+
+    pub fn get_level(&self, villager: &Villager) -> usize {
+        let affection = self.get_affection(villager);
+        AFFECTION_LEVELS.iter().filter(|threshold| affection >= **threshold).count()
+    }
+
+    /// Perks of all villagers, as far as their affection reaches.
+    pub fn unlocked_perks(&self) -> Vec<VillagerPerk> {
+        Villager::iter()
+            .flat_map(|villager| villager.get_perks().into_iter().take(self.get_level(&villager)))
+            .collect()
+    }
+
+    pub fn shop_discount(&self) -> f64 {
+        self.unlocked_perks().iter()
+            .map(|perk| match perk {
+                VillagerPerk::ShopDiscount(discount) => *discount,
+                _ => 0.0,
+            })
+            .sum()
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        self.unlocked_perks().iter()
+            .filter_map(|perk| match perk {
+                VillagerPerk::SkillBonus(skill, modifiers) if *skill == job_archetype.get_skill_type() => Some(*modifiers),
+                _ => None,
+            })
+            .fold(JobModifiers::none(), |total, modifiers| total.combine(&modifiers))
+    }
+}
+
+/// Hands one of the item to the villager. New levels unlock their perks right away.
+pub fn gift_item(state: &mut GameState, villager: Villager, item: Item) -> bool {
+    if item == Item::Coin || state.inventory.get_item_amount(&item) < 1 {
+        return false;
+    }
+
+    let level_before = state.villagers.get_level(&villager);
+    let affection = match villager.get_preference(&item) {
+        Preference::Loved => LOVED_GIFT_AFFECTION,
+        Preference::Liked => LIKED_GIFT_AFFECTION,
+        Preference::Neutral => OTHER_GIFT_AFFECTION,
+    };
+
+    state.inventory.add_item(item, -1);
+    *state.villagers.affection.entry(villager).or_insert(0) += affection;
+
+    let level = state.villagers.get_level(&villager);
+
+    for perk in villager.get_perks().iter().take(level).skip(level_before) {
+        if let VillagerPerk::Teaches(node) = perk {
+            teach_research(state, *node);
+        }
+    }
+
+    if level > level_before {
+        state.toasts.push(Toast {
+            text: format!("{} is now your {}", villager.get_name(), affection_level_name(level)),
+            remaining_seconds: TOAST_SECONDS,
+        });
+    }
+
+    true
+}
+
+/// Completes the node without paying for it. Research into the same node stops.
+fn teach_research(state: &mut GameState, node: ResearchNode) {
+    if state.research.is_completed(&node) {
+        return;
+    }
+
+    if state.research.active.as_ref().is_some_and(|active| active.node == node) {
+        state.research.active = None;
+    }
+
+    state.research.completed.push(node);
+}
+
+pub fn build_villagers_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let villager_count = Villager::iter().count() as f32;
+    let column_width = (rect.w - padding * (villager_count + 1.0)) / villager_count;
+
+    for (i, villager) in Villager::iter().enumerate() {
+        let x = rect.x + padding + i as f32 * (column_width + padding);
+        let mut y = rect.y + padding;
+        let affection = state.villagers.get_affection(&villager);
+        let level = state.villagers.get_level(&villager);
+
+        elements.push(UiElement::Text {
+            content: format!("{} {}", villager.get_name(), villager.get_title()),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 24.0,
+            font_size: 24.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        y += 36.0;
+
+        let (progress, next) = match AFFECTION_LEVELS.get(level) {
+            Some(next) => {
+                let previous = if level > 0 { AFFECTION_LEVELS[level - 1] } else { 0 };
+                ((affection - previous) as f64 / (next - previous) as f64, format!("{} / {}", affection, next))
+            }
+            None => (1.0, affection.to_string()),
+        };
+
+        elements.push(UiElement::Text {
+            content: format!("{} - {}", affection_level_name(level), next),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 14.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        });
+
+        elements.push(UiElement::ProgressBar {
+            x,
+            y: y + 22.0,
+            width: column_width,
+            height: 10.0,
+            progress,
+            background_color: palette::BAR_BACKGROUND.get_color(),
+            foreground_color: PaletteC::Coral.get_color(),
+            border_style: BorderStyle::Solid,
+        });
+
+        y += 48.0;
+
+        elements.push(UiElement::Text {
+            content: format!("\"{}\"", villager.get_dialog(level)),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 16.0,
+            font_size: 16.0,
+            color: palette::TEXT.get_color(),
+        });
+
+        y += 40.0;
+
+        for (j, perk) in villager.get_perks().iter().enumerate() {
+            let unlocked = j < level;
+
+            elements.push(UiElement::Text {
+                content: format!("{}: {}", affection_level_name(j + 1), perk.get_description()),
+                font: if unlocked { assets.fonts.text_bold.clone() } else { assets.fonts.text.clone() },
+                x,
+                y: y + 14.0 + j as f32 * 22.0,
+                font_size: 14.0,
+                color: if unlocked { palette::TEXT.get_color() } else { palette::BORDER.get_color() },
+            });
+        }
+
+        y += 86.0;
+
+        for (j, item) in villager.get_favorite_items().iter().enumerate() {
+            let amount = state.inventory.get_item_amount(item);
+            let loved = villager.get_preference(item) == Preference::Loved;
+
+            elements.push(UiElement::RectButton {
+                rectangle: UiRect::new(x, y + j as f32 * 40.0, column_width, 32.0),
+                font_size: 14.0,
+                font: assets.fonts.text_bold.clone(),
+                text: format!("Give {} ({}){}", item.get_name(), amount, if loved { " - loved" } else { "" }),
+                background_color: if amount > 0 { palette::BUTTON_BACKGROUND.get_color() } else { PaletteC::Coral.get_color() },
+                text_color: palette::BUTTON_TEXT.get_color(),
+                intent: Intent::GiftItem(villager, *item),
+                parent_clip: None,
+                border_style: BorderStyle::Solid,
+            });
+        }
+    }
+
+    elements
+}
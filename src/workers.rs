@@ -0,0 +1,403 @@
+use crate::toasts::{Toast, TOAST_SECONDS};
+use crate::assets::AssetId::ParchmentFrame;
+use crate::assets::Assets;
+use crate::counts_actions::CountsActions;
+use crate::draw::{button, UiElement};
+use crate::game::{pretty_duration, GameState, Intent, Inventory, Item, UiRect};
+use crate::job::{JobArchetype, JobModifiers};
+use crate::palette;
+use crate::palette::PaletteC;
+use crate::rng::Rng;
+use crate::skill::SkillCategory;
+use strum::IntoEnumIterator;
+use strum_macros::EnumIter;
+
+pub const MAX_WORKERS: usize = 6;
+pub const MAX_CANDIDATES: usize = 3;
+pub const CANDIDATE_REFRESH_SECONDS: f64 = 10.0 * 60.0;
+
+/// How long a worker stays without being paid before they leave.
+pub const WORKER_PATIENCE_SECONDS: f64 = 10.0 * 60.0;
+
+/// Each worker level above 1 makes their slot this much faster.
+pub const WORKER_LEVEL_SPEED_BONUS: f64 = 0.02;
+
+const WORKER_NAMES: [&str; 12] = [
+    "Ada", "Bram", "Cora", "Dain", "Edda", "Finn", "Greta", "Hob", "Ilse", "Jory", "Kit", "Lotte",
+];
+
+#[derive(EnumIter, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum WorkerTrait {
+    FastGatherer,
+    CarefulCrafter,
+    Lazy,
+}
+
+impl WorkerTrait {
+    pub fn get_name(&self) -> &str {
+        match self {
+            WorkerTrait::FastGatherer => "Fast Gatherer",
+            WorkerTrait::CarefulCrafter => "Careful Crafter",
+            WorkerTrait::Lazy => "Lazy",
+        }
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        let skill_type = job_archetype.get_skill_type();
+
+        match self {
+            WorkerTrait::FastGatherer if SkillCategory::Gathering.get_skill_archetypes().contains(&skill_type) => {
+                JobModifiers { speed_multiplier: 1.2, ..JobModifiers::none() }
+            }
+            WorkerTrait::CarefulCrafter if SkillCategory::Crafting.get_skill_archetypes().contains(&skill_type) => {
+                JobModifiers { yield_multiplier: 1.2, ..JobModifiers::none() }
+            }
+            WorkerTrait::Lazy => JobModifiers { speed_multiplier: 0.85, ..JobModifiers::none() },
+            _ => JobModifiers::none(),
+        }
+    }
+}
+
+/// What a worker wants per hour.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Upkeep {
+    Coins(i64),
+    Sandwiches(i64),
+}
+
+impl Upkeep {
+    pub fn item(&self) -> Item {
+        match self {
+            Upkeep::Coins(_) => Item::Coin,
+            Upkeep::Sandwiches(_) => Item::Sandwich,
+        }
+    }
+
+    pub fn per_hour(&self) -> i64 {
+        match self {
+            Upkeep::Coins(amount) | Upkeep::Sandwiches(amount) => *amount,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        format!("{} {}/h", self.per_hour(), self.item().get_name())
+    }
+}
+
+pub struct Worker {
+    pub name: String,
+    pub traits: Vec<WorkerTrait>,
+    pub upkeep: Upkeep,
+    pub experience: CountsActions,
+    pub assigned_slot: Option<usize>,
+    pub upkeep_accumulator: f64,
+    pub unpaid_seconds: f64,
+}
+
+impl Worker {
+    pub fn new(name: &str, traits: Vec<WorkerTrait>, upkeep: Upkeep) -> Self {
+        Self {
+            name: name.to_string(),
+            traits,
+            upkeep,
+            experience: CountsActions::new(Self::actions_to_level, 10),
+            assigned_slot: None,
+            upkeep_accumulator: 0.0,
+            unpaid_seconds: 0.0,
+        }
+    }
+
+    fn actions_to_level(level: i64) -> i64 {
+        5 * level * level
+    }
+
+    pub fn has_trait(&self, worker_trait: &WorkerTrait) -> bool {
+        self.traits.contains(worker_trait)
+    }
+
+    /// Coins to hire the worker, more for better traits.
+    pub fn hire_cost(&self) -> i64 {
+        let trait_cost: i64 = self.traits.iter()
+            .map(|worker_trait| if *worker_trait == WorkerTrait::Lazy { -25 } else { 75 })
+            .sum();
+
+        (50 + trait_cost).max(25)
+    }
+
+    pub fn get_modifiers(&self, job_archetype: &JobArchetype) -> JobModifiers {
+        let level_modifiers = JobModifiers {
+            speed_multiplier: 1.0 + (self.experience.level - 1) as f64 * WORKER_LEVEL_SPEED_BONUS,
+            ..JobModifiers::none()
+        };
+
+        self.traits.iter()
+            .map(|worker_trait| worker_trait.get_modifiers(job_archetype))
+            .fold(level_modifiers, |total, modifiers| total.combine(&modifiers))
+    }
+
+    /// Pays the upkeep that is due. Returns false once the worker ran out of patience.
+    pub fn pay_upkeep(&mut self, inventory: &mut Inventory, dt: f32) -> bool {
+        self.upkeep_accumulator += self.upkeep.per_hour() as f64 * dt as f64 / 3600.0;
+
+        let due = self.upkeep_accumulator.floor() as i64;
+        let item = self.upkeep.item();
+
+        if due > 0 && inventory.get_item_amount(&item) >= due {
+            inventory.add_item(item, -due);
+            self.upkeep_accumulator -= due as f64;
+            self.unpaid_seconds = 0.0;
+        } else if due > 0 {
+            self.unpaid_seconds += dt as f64;
+        }
+
+        self.unpaid_seconds < WORKER_PATIENCE_SECONDS
+    }
+}
+
+/// Hired workers and the people looking for work.
+pub struct Workers {
+    pub hired: Vec<Worker>,
+    pub candidates: Vec<Worker>,
+    pub refresh_timer: f64,
+}
+
+impl Workers {
+    pub fn new() -> Self {
+        Self {
+            hired: vec![],
+            candidates: vec![],
+            refresh_timer: 0.0,
+        }
+    }
+
+    pub fn get_assigned(&self, slot_index: usize) -> Option<&Worker> {
+        self.hired.iter().find(|worker| worker.assigned_slot == Some(slot_index))
+    }
+
+    pub fn get_assigned_mut(&mut self, slot_index: usize) -> Option<&mut Worker> {
+        self.hired.iter_mut().find(|worker| worker.assigned_slot == Some(slot_index))
+    }
+
+    pub fn get_modifiers(&self, slot_index: usize, job_archetype: &JobArchetype) -> JobModifiers {
+        self.get_assigned(slot_index)
+            .map(|worker| worker.get_modifiers(job_archetype))
+            .unwrap_or_else(JobModifiers::none)
+    }
+
+    /// Pays everyone and brings in new candidates every few minutes. Returns the workers who left unpaid.
+    pub fn update(&mut self, rng: &mut Rng, inventory: &mut Inventory, dt: f32) -> Vec<Worker> {
+        let mut left = vec![];
+        let mut stayed = vec![];
+
+        for mut worker in self.hired.drain(..) {
+            if worker.pay_upkeep(inventory, dt) {
+                stayed.push(worker);
+            } else {
+                left.push(worker);
+            }
+        }
+
+        self.hired = stayed;
+
+        self.refresh_timer -= dt as f64;
+
+        if self.refresh_timer <= 0.0 {
+            self.refresh_timer += CANDIDATE_REFRESH_SECONDS;
+
+            if self.candidates.len() < MAX_CANDIDATES {
+                self.candidates.push(generate_candidate(rng));
+            }
+        }
+
+        left
+    }
+}
+
+pub fn generate_candidate(rng: &mut Rng) -> Worker {
+    let name = WORKER_NAMES[rng.index(WORKER_NAMES.len())];
+    let all_traits: Vec<WorkerTrait> = WorkerTrait::iter().collect();
+    let trait_count = if rng.chance(0.3) { 2 } else { 1 };
+    let mut traits = vec![];
+
+    while traits.len() < trait_count {
+        let worker_trait = all_traits[rng.index(all_traits.len())];
+
+        if !traits.contains(&worker_trait) {
+            traits.push(worker_trait);
+        }
+    }
+
+    let lazy = traits.contains(&WorkerTrait::Lazy);
+
+    let upkeep = if rng.chance(0.5) {
+        Upkeep::Coins(if lazy { 10 } else { 20 })
+    } else {
+        Upkeep::Sandwiches(if lazy { 1 } else { 2 })
+    };
+
+    Worker::new(name, traits, upkeep)
+}
+
+pub fn worker_left_toast(worker: &Worker) -> Toast {
+    Toast {
+        text: format!("{} quit, nobody paid them", worker.name),
+        remaining_seconds: TOAST_SECONDS,
+    }
+}
+
+/// Hired workers, counting the ones away on expeditions since they come back.
+pub fn worker_count(state: &GameState) -> usize {
+    state.workers.hired.len() + state.expeditions.active.iter().map(|expedition| expedition.party.len()).sum::<usize>()
+}
+
+pub fn hire_worker(state: &mut GameState, candidate_index: usize) -> bool {
+    let Some(candidate) = state.workers.candidates.get(candidate_index) else {
+        return false;
+    };
+
+    let cost = candidate.hire_cost();
+
+    if worker_count(state) >= MAX_WORKERS || state.inventory.get_item_amount(&Item::Coin) < cost {
+        return false;
+    }
+
+    state.inventory.add_item(Item::Coin, -cost);
+    let worker = state.workers.candidates.remove(candidate_index);
+    state.workers.hired.push(worker);
+
+    true
+}
+
+/// The worker leaves for good and takes their experience with them.
+pub fn fire_worker(state: &mut GameState, worker_index: usize) -> bool {
+    if worker_index >= state.workers.hired.len() {
+        return false;
+    }
+
+    state.workers.hired.remove(worker_index);
+
+    true
+}
+
+/// Moves the worker to the slot, or takes them off their slot. Whoever worked the slot before is unassigned.
+pub fn assign_worker(state: &mut GameState, worker_index: usize, slot_index: Option<usize>) -> bool {
+    if worker_index >= state.workers.hired.len() || slot_index.is_some_and(|index| index >= state.job_slots.len()) {
+        return false;
+    }
+
+    if let Some(previous) = slot_index.and_then(|index| state.workers.get_assigned_mut(index)) {
+        previous.assigned_slot = None;
+    }
+
+    state.workers.hired[worker_index].assigned_slot = slot_index;
+
+    true
+}
+
+pub fn build_workers_ui(state: &GameState, assets: &Assets, rect: UiRect) -> Vec<UiElement> {
+    let mut elements = vec![];
+
+    elements.push(UiElement::NinePatch {
+        x: rect.x,
+        y: rect.y,
+        width: rect.w,
+        height: rect.h,
+        texture: ParchmentFrame.get_texture(assets),
+    });
+
+    let padding = 24.0;
+    let row_height = 64.0;
+    let column_width = (rect.w - padding * 3.0) / 2.0;
+    let hired_x = rect.x + padding;
+    let candidates_x = hired_x + column_width + padding;
+
+    elements.push(UiElement::Text {
+        content: format!("Workers {} / {}", worker_count(state), MAX_WORKERS),
+        font: assets.fonts.text_bold.clone(),
+        x: hired_x,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    elements.push(UiElement::Text {
+        content: format!("Looking for work - more in {}", pretty_duration(state.workers.refresh_timer)),
+        font: assets.fonts.text_bold.clone(),
+        x: candidates_x,
+        y: rect.y + padding + 24.0,
+        font_size: 24.0,
+        color: palette::TEXT.get_color(),
+    });
+
+    for (i, worker) in state.workers.hired.iter().enumerate() {
+        let y = rect.y + padding + 50.0 + i as f32 * row_height;
+
+        elements.extend(worker_row(worker, assets, hired_x, y));
+
+        if worker.unpaid_seconds > 0.0 {
+            elements.push(UiElement::Text {
+                content: format!("Unpaid, leaves in {}", pretty_duration(WORKER_PATIENCE_SECONDS - worker.unpaid_seconds)),
+                font: assets.fonts.text_bold.clone(),
+                x: hired_x + 300.0,
+                y: y + 56.0,
+                font_size: 12.0,
+                color: PaletteC::Coral.get_color(),
+            });
+        }
+
+        // clicking cycles through the slots and back to unassigned
+        let next_slot = match worker.assigned_slot {
+            None => Some(0),
+            Some(index) if index + 1 < state.job_slots.len() => Some(index + 1),
+            Some(_) => None,
+        };
+
+        let slot_text = match worker.assigned_slot {
+            Some(index) => format!("Slot {}", index + 1),
+            None => "Unassigned".to_string(),
+        };
+
+        elements.push(button(assets, UiRect::new(hired_x + column_width - 200.0, y + 8.0, 120.0, 32.0),
+            &slot_text, true, Intent::AssignWorker(i, next_slot), None));
+        elements.push(button(assets, UiRect::new(hired_x + column_width - 72.0, y + 8.0, 72.0, 32.0),
+            "Fire", true, Intent::FireWorker(i), None));
+    }
+
+    for (i, candidate) in state.workers.candidates.iter().enumerate() {
+        let y = rect.y + padding + 50.0 + i as f32 * row_height;
+        let cost = candidate.hire_cost();
+        let can_hire = worker_count(state) < MAX_WORKERS && state.inventory.get_item_amount(&Item::Coin) >= cost;
+
+        elements.extend(worker_row(candidate, assets, candidates_x, y));
+        elements.push(button(assets, UiRect::new(candidates_x + column_width - 140.0, y + 8.0, 140.0, 32.0),
+            &format!("Hire for {} Coins", cost), can_hire, Intent::HireWorker(i), None));
+    }
+
+    elements
+}
+
+fn worker_row(worker: &Worker, assets: &Assets, x: f32, y: f32) -> Vec<UiElement> {
+    let traits = worker.traits.iter()
+        .map(|worker_trait| worker_trait.get_name())
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    vec![
+        UiElement::Text {
+            content: format!("{} Lv. {}", worker.name, worker.experience.level),
+            font: assets.fonts.text_bold.clone(),
+            x,
+            y: y + 18.0,
+            font_size: 18.0,
+            color: palette::TEXT.get_color(),
+        },
+        UiElement::Text {
+            content: format!("{} - {}", traits, worker.upkeep.describe()),
+            font: assets.fonts.text.clone(),
+            x,
+            y: y + 38.0,
+            font_size: 14.0,
+            color: palette::BORDER.get_color(),
+        },
+    ]
+}
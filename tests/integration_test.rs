@@ -1,4 +1,28 @@
-use tiny_fields::game::{GameState, Intent, Item};
+use tiny_fields::game::{GameState, Intent, Item, WoodItem, WoodWorkingItem, MAX_OFFLINE_SECONDS};
+use tiny_fields::equipment::{Tool, TOOL_DURABILITY};
+use tiny_fields::expeditions::Destination;
+use tiny_fields::combat::Enemy;
+use tiny_fields::achievements::Achievement;
+use tiny_fields::automation::{Rule, RuleAction, RuleCondition};
+use tiny_fields::buffs::{BuffKind, MAX_HYPER_SECONDS};
+use tiny_fields::job::{AlchemyJobArchetype, CookingJobArchetype, ForagingJobArchetype, HuntingJobArchetype, JobArchetype, JobInstance, JobModifiers, JobParameters, LumberingJobArchetype, MiningJobArchetype, SmithingJobArchetype, WoodworkingJobArchetype, BASE_TIMESLOT_CAPACITY, CLICKS_PER_HYPER_ACTION, CLICK_PROGRESS_SECONDS, MIN_CLICK_INTERVAL};
+use tiny_fields::market::{base_price, Market, MAX_SPIKE_FACTOR};
+use tiny_fields::optimizer::{apply_plan, optimize_slots, optimize_slots_for};
+use tiny_fields::orders::{generate_order, MAX_ORDERS};
+use tiny_fields::prestige::{renown_for_reset, Prestige, PrestigePerk};
+use tiny_fields::job_slot::{JobSlotState, QueuedJob, StopCondition};
+use tiny_fields::rates::ProductionReport;
+use tiny_fields::research::ResearchNode;
+use tiny_fields::resource_nodes::{PROSPECTED_VEIN_CAPACITY, PROSPECT_COST};
+use tiny_fields::rng::Rng;
+use tiny_fields::runes::Rune;
+use tiny_fields::shop::{discounted_buy_price, Upgrade, MAX_LONGER_SHIFTS, SHOP_STOCK};
+use tiny_fields::skill::SkillArchetype;
+use tiny_fields::territory::{TileKind, TILE_YIELD_BONUS};
+use tiny_fields::village::{Building, BASE_STORAGE, STORAGE_PER_WAREHOUSE_LEVEL};
+use tiny_fields::villagers::{Villager, AFFECTION_LEVELS};
+use tiny_fields::workers::{worker_count, Upkeep, Worker, WorkerTrait, MAX_WORKERS};
+use strum::IntoEnumIterator;
 
 #[test]
 fn it_works() {
@@ -8,4 +32,994 @@ fn it_works() {
     game_state.step(intents.as_slice(), 0.016);
 
     assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn production_report_nets_out_running_slots() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.job_slots[1].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Cooking(CookingJobArchetype::Sandwich),
+    }));
+    game_state.inventory.add_item(Item::Wood(WoodItem::Craftwood), 90);
+    game_state.inventory.add_item(Item::Wood(WoodItem::Kindlewood), 10);
+    game_state.inventory.add_item(Item::Meat, 10);
+    game_state.inventory.add_item(Item::Herb, 10);
+
+    let report = ProductionReport::from_state(&game_state);
+    let craftwood = report.get_flow(&Item::Wood(WoodItem::Craftwood));
+
+    // one action every 4 seconds, the sandwich needs 2 craftwood
+    assert_eq!(craftwood.produced_per_hour, 900.0);
+    assert_eq!(craftwood.consumed_per_hour, 1800.0);
+    assert_eq!(report.get_flow(&Item::Sandwich).net_per_hour(), 900.0);
+    assert_eq!(report.get_flow(&Item::Wood(WoodItem::Kindlewood)).consumed_per_hour, 900.0);
+    assert_eq!(report.seconds_until_empty(&Item::Wood(WoodItem::Craftwood), &game_state.inventory), Some(360.0));
+    assert_eq!(report.seconds_until_empty(&Item::Sandwich, &game_state.inventory), None);
+
+    // jobs without their inputs or with a full storage make nothing
+    game_state.inventory.add_item(Item::Meat, -10);
+    game_state.inventory.add_item(Item::Wood(WoodItem::Craftwood), BASE_STORAGE);
+    let report = ProductionReport::from_state(&game_state);
+    assert_eq!(report.get_flow(&Item::Sandwich).net_per_hour(), 0.0);
+    assert_eq!(report.get_flow(&Item::Wood(WoodItem::Craftwood)).produced_per_hour, 0.0);
+}
+
+#[test]
+fn optimizer_balances_sandwich_inputs() {
+    let plan = optimize_slots(Item::Sandwich, 4);
+
+    // a cook uses two of each input per action, so one gatherer per input keeps it at half speed
+    assert_eq!(plan.jobs.len(), 4);
+    assert_eq!(plan.target_per_hour, 450.0);
+
+    // coins value the whole output at base prices, without the inputs it used up. Every job is a candidate,
+    // so planning every slot with all time slots bought must still be quick
+    let started = std::time::Instant::now();
+    let coin_plan = optimize_slots_for(Item::Coin, 6, BASE_TIMESLOT_CAPACITY + MAX_LONGER_SHIFTS as i32, |_| true, |_| JobModifiers::none());
+    assert!(started.elapsed().as_secs_f64() < 2.0, "planning for coins took {:?}", started.elapsed());
+    assert!(!coin_plan.jobs.is_empty());
+    assert!(coin_plan.target_per_hour > 0.0);
+
+    // twice as fast gatherers keep the cook at full speed
+    let fast_gatherers = |job: &JobArchetype| match job {
+        JobArchetype::Cooking(_) => JobModifiers::none(),
+        _ => JobModifiers { speed_multiplier: 2.0, ..JobModifiers::none() },
+    };
+    let fast_plan = optimize_slots_for(Item::Sandwich, 4, i32::MAX, |_| true, fast_gatherers);
+    assert_eq!(fast_plan.target_per_hour, 900.0);
+}
+
+#[test]
+fn optimize_intent_fills_unlocked_slots() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::Empty;
+    game_state.job_slots[1].state = JobSlotState::Empty;
+    game_state.research.completed.push(ResearchNode::Herbalism);
+
+    game_state.step(&[Intent::OptimizeSlots(Item::ManaPotion)], 0.0);
+
+    let running: Vec<JobArchetype> = game_state.job_slots.iter()
+        .filter_map(|slot| match &slot.state {
+            JobSlotState::RunningJob(job_instance) => Some(job_instance.job_archetype),
+            _ => None,
+        })
+        .collect();
+
+    assert_eq!(running.len(), 2);
+    assert!(running.contains(&JobArchetype::Foraging(ForagingJobArchetype::Herb)));
+    assert!(running.contains(&JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion)));
+    assert!(matches!(game_state.job_slots[2].state, JobSlotState::Locked));
+
+    // a third herb slot takes an open slot first, then the cheapest other job, and leaves the rest alone
+    let deer = JobArchetype::Hunting(HuntingJobArchetype::Deer);
+    let craftwood = JobArchetype::Lumbering(LumberingJobArchetype::Craftwood);
+    game_state.job_slots[2].state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: deer }));
+    game_state.job_slots[3].state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: craftwood }));
+    game_state.step(&[Intent::OptimizeSlots(Item::ManaPotion)], 0.0);
+
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[2].state else { panic!("unrelated job should keep running") };
+    assert_eq!(job_instance.job_archetype, deer);
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[3].state else { panic!("slot should run the plan") };
+    assert_eq!(job_instance.job_archetype, JobArchetype::Foraging(ForagingJobArchetype::Herb));
+}
+
+#[test]
+fn optimize_intent_makes_room_on_a_full_board() {
+    let mut game_state = GameState::new();
+    game_state.research.completed.push(ResearchNode::Smelting);
+    let gatherers = [
+        JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+        JobArchetype::Lumbering(LumberingJobArchetype::Kindlewood),
+        JobArchetype::Hunting(HuntingJobArchetype::Deer),
+        JobArchetype::Foraging(ForagingJobArchetype::Herb),
+        JobArchetype::Mining(MiningJobArchetype::Iron),
+    ];
+    for (slot, job_archetype) in game_state.job_slots.iter_mut().zip(gatherers) {
+        slot.state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype }));
+    }
+    assert_eq!(game_state.timeslots_used(None), game_state.timeslot_capacity());
+
+    apply_plan(&mut game_state, Item::IronBar);
+
+    let running: Vec<JobArchetype> = game_state.job_slots.iter()
+        .filter(|slot| game_state.is_slot_active(slot))
+        .map(|slot| match &slot.state {
+            JobSlotState::RunningJob(job_instance) => job_instance.job_archetype,
+            _ => unreachable!(),
+        })
+        .collect();
+
+    // the smith needs two time slots, so two of the cheapest gatherers make way and the miner stays
+    assert!(running.contains(&JobArchetype::Smithing(SmithingJobArchetype::IronBar)));
+    assert!(running.contains(&JobArchetype::Mining(MiningJobArchetype::Iron)));
+    assert_eq!(game_state.timeslots_used(None), game_state.timeslot_capacity());
+
+    game_state.step(&[Intent::SkipSeconds(60)], 0.0);
+    assert!(game_state.inventory.get_item_amount(&Item::IronBar) > 0);
+}
+
+#[test]
+fn job_queue_switches_after_goal() {
+    let mut game_state = GameState::new();
+    game_state.research.completed.push(ResearchNode::Herbalism);
+    game_state.job_slots[0].set_state(JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Foraging(ForagingJobArchetype::Herb),
+    })));
+    game_state.step(&[
+        Intent::SetStopCondition(0, StopCondition::ActionsCompleted(3)),
+        Intent::QueueJob(0, QueuedJob {
+            job_archetype: JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion),
+            stop_condition: StopCondition::ItemAtLeast(Item::ManaPotion, 1),
+        }),
+    ], 0.0);
+
+    // three herbs, two of which the potion takes right away
+    game_state.step(&[Intent::SkipSeconds(12)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Herb), 1);
+
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[0].state else { panic!("slot should be running") };
+    assert_eq!(job_instance.job_archetype, JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion));
+
+    // the last goal pauses the slot because nothing else is queued
+    game_state.step(&[Intent::SkipSeconds(8)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 1);
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[0].state else { panic!("slot should be running") };
+    assert!(!job_instance.running);
+
+    // switching jobs gives back the herbs the potion already took for its next brew
+    game_state.inventory.add_item(Item::Herb, 4);
+    game_state.job_slots[1].set_state(JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion),
+    })));
+    game_state.step(&[
+        Intent::SetStopCondition(1, StopCondition::ItemAtLeast(Item::ManaPotion, 2)),
+        Intent::QueueJob(1, QueuedJob { job_archetype: JobArchetype::Foraging(ForagingJobArchetype::Herb), stop_condition: StopCondition::Never }),
+        Intent::SkipSeconds(4),
+    ], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 2);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Herb), 3);
+}
+
+#[test]
+fn rules_switch_slots_and_survive_a_save() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[1].set_state(JobSlotState::Empty);
+    game_state.step(&[Intent::AddRule(Rule {
+        enabled: true,
+        condition: RuleCondition::ItemBelow(Item::Meat, 20),
+        action: RuleAction::SwitchSlot(1, JobArchetype::Hunting(HuntingJobArchetype::Deer)),
+    })], 1.0);
+
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[1].state else { panic!("rule should start the job") };
+    assert_eq!(job_instance.job_archetype, JobArchetype::Hunting(HuntingJobArchetype::Deer));
+    // the rule keeps holding but must not restart the running job
+    assert_eq!(job_instance.time_accumulator, 1.0);
+
+    game_state.step(&[], 1.0);
+    let JobSlotState::RunningJob(job_instance) = &mut game_state.job_slots[1].state else { panic!() };
+    job_instance.hyper_mode.is_enabled = true;
+    job_instance.hyper_mode.hyper_time_accumulator = 3.0;
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+
+    assert_eq!(loaded.automation.rules, game_state.automation.rules);
+    let JobSlotState::RunningJob(job_instance) = &loaded.job_slots[1].state else { panic!("slot should be saved") };
+    assert_eq!(job_instance.time_accumulator, 2.0);
+    assert!(job_instance.hyper_mode.is_enabled);
+    assert_eq!(job_instance.hyper_mode.hyper_time_accumulator, 3.0);
+}
+
+#[test]
+fn rules_give_back_paid_inputs_and_leave_picking_slots_alone() {
+    let mut game_state = GameState::new();
+    game_state.research.completed.push(ResearchNode::Herbalism);
+    game_state.job_slots[0].set_state(JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion),
+    })));
+    game_state.job_slots[1].set_state(JobSlotState::PickingCategory);
+    game_state.inventory.add_item(Item::Herb, 2);
+
+    // the potion takes its herbs right away
+    game_state.step(&[], 1.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Herb), 0);
+
+    game_state.step(&[
+        Intent::AddRule(Rule {
+            enabled: true,
+            condition: RuleCondition::ItemBelow(Item::Herb, 10),
+            action: RuleAction::SwitchSlot(0, JobArchetype::Foraging(ForagingJobArchetype::Herb)),
+        }),
+        Intent::AddRule(Rule {
+            enabled: true,
+            condition: RuleCondition::ItemBelow(Item::Meat, 10),
+            action: RuleAction::SwitchSlot(1, JobArchetype::Hunting(HuntingJobArchetype::Deer)),
+        }),
+    ], 0.0);
+
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[0].state else { panic!("rule should switch the slot") };
+    assert_eq!(job_instance.job_archetype, JobArchetype::Foraging(ForagingJobArchetype::Herb));
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Herb), 2);
+    assert!(matches!(game_state.job_slots[1].state, JobSlotState::PickingCategory));
+}
+
+#[test]
+fn every_produced_or_dropped_item_is_listed() {
+    let all_items = Item::all();
+    let products = JobArchetype::all().into_iter().map(|job| job.get_product());
+    let loot = Destination::iter().flat_map(|destination| destination.get_loot_table())
+        .chain(Enemy::iter().flat_map(|enemy| enemy.get_drops()))
+        .map(|entry| entry.item);
+
+    for item in products.chain(loot) {
+        assert!(all_items.contains(&item), "{} is missing from Item::all()", item.get_name());
+    }
+}
+
+#[test]
+fn sandwiches_and_mana_potions_buff_jobs_up_to_a_cap() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.job_slots[1].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Woodworking(WoodworkingJobArchetype::Plank),
+    }));
+    game_state.inventory.add_item(Item::Sandwich, 20);
+
+    // well fed speeds up gathering only, and eating more stacks up to the cap
+    game_state.step(&[Intent::UseItem(Item::Sandwich, None)], 0.0);
+    assert!((game_state.slot_modifiers(&game_state.job_slots[0]).speed_multiplier - 1.25).abs() < 1e-9);
+    assert_eq!(game_state.slot_modifiers(&game_state.job_slots[1]).speed_multiplier, 1.0);
+
+    let intents: Vec<Intent> = (0..15).map(|_| Intent::UseItem(Item::Sandwich, None)).collect();
+    game_state.step(&intents, 0.0);
+    assert_eq!(game_state.buffs.active.len(), 1);
+    assert_eq!(game_state.buffs.active[0].remaining_seconds, BuffKind::WellFed.max_duration());
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.buffs.active, game_state.buffs.active);
+
+    // a potion first charges hyper mode, then extends it while it runs
+    game_state.inventory.add_item(Item::ManaPotion, 10);
+    game_state.step(&[Intent::UseItem(Item::ManaPotion, Some(0))], 0.0);
+    let JobSlotState::RunningJob(job) = &game_state.job_slots[0].state else { panic!() };
+    assert!(job.hyper_mode.has_enough_actions());
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 9);
+
+    game_state.step(&[Intent::UseItem(Item::ManaPotion, Some(0))], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 9);
+
+    let intents: Vec<Intent> = (0..9).map(|_| Intent::UseItem(Item::ManaPotion, Some(0))).collect();
+    game_state.step(&[Intent::EnableHyperMode(0)], 0.0);
+    game_state.step(&intents, 0.0);
+    let JobSlotState::RunningJob(job) = &game_state.job_slots[0].state else { panic!() };
+    let remaining = job.hyper_mode.hyper_duration_seconds - job.hyper_mode.hyper_time_accumulator;
+    assert!((remaining - MAX_HYPER_SECONDS).abs() < 1e-9);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 4);
+}
+
+#[test]
+fn tools_wear_down_until_they_break() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.inventory.add_item(Item::Tool(Tool::Axe), 2);
+
+    // a skill keeps its tool until it breaks, the spare stays in the inventory
+    game_state.step(&[Intent::EquipTool(Tool::Axe), Intent::EquipTool(Tool::Axe)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Tool(Tool::Axe)), 1);
+    assert_eq!(game_state.equipment.get_tool_for_skill(&SkillArchetype::Lumbering).unwrap().durability, TOOL_DURABILITY);
+
+    for _ in 0..10 {
+        game_state.step(&[], 1.0);
+    }
+    let durability = game_state.equipment.get_tool_for_skill(&SkillArchetype::Lumbering).unwrap().durability;
+    assert_eq!(durability, TOOL_DURABILITY - 3);
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.equipment.equipped, game_state.equipment.equipped);
+
+    for _ in 0..durability {
+        game_state.equipment.wear(&SkillArchetype::Lumbering);
+    }
+    assert!(game_state.equipment.get_tool_for_skill(&SkillArchetype::Lumbering).is_none());
+
+    game_state.step(&[Intent::EquipTool(Tool::Axe)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Tool(Tool::Axe)), 0);
+    assert!(game_state.equipment.get_tool_for_skill(&SkillArchetype::Lumbering).is_some());
+}
+
+#[test]
+fn unlocking_slots_costs_planks() {
+    let mut game_state = GameState::new();
+    let plank = Item::Woodworking(WoodWorkingItem::Plank);
+    game_state.inventory.add_item(plank, 15);
+
+    game_state.step(&[Intent::UnlockJobSlot(1), Intent::UnlockJobSlot(2), Intent::UnlockJobSlot(3)], 0.0);
+
+    // the second slot is free, the third costs 10 planks and the fourth 30
+    assert!(matches!(game_state.job_slots[1].state, JobSlotState::PickingCategory));
+    assert!(matches!(game_state.job_slots[2].state, JobSlotState::PickingCategory));
+    assert!(matches!(game_state.job_slots[3].state, JobSlotState::Locked));
+    assert_eq!(game_state.inventory.get_item_amount(&plank), 5);
+
+    // deleting a job leaves the paid slot open instead of locking it again
+    game_state.job_slots[2].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.step(&[Intent::ChangeJobSlotState(2, JobSlotState::Empty)], 0.0);
+    assert!(matches!(game_state.job_slots[2].state, JobSlotState::Empty));
+
+    game_state.step(&[Intent::UnlockJobSlot(2)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&plank), 5);
+}
+
+#[test]
+fn crafting_burns_fuel_or_runs_slower() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Smithing(SmithingJobArchetype::IronBar),
+    }));
+    game_state.inventory.add_item(Item::IronOre, 100);
+    game_state.inventory.add_item(Item::Wood(WoodItem::Kindlewood), 1);
+
+    // the first bar burns the only kindlewood, the second one takes twice as long
+    game_state.step(&[], 4.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::IronBar), 1);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Wood(WoodItem::Kindlewood)), 0);
+
+    game_state.step(&[], 4.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::IronBar), 1);
+
+    game_state.step(&[], 4.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::IronBar), 2);
+}
+
+#[test]
+fn shop_purchases_are_paid_with_coins() {
+    let mut game_state = GameState::new();
+    game_state.inventory.add_item(Item::Sandwich, 10);
+
+    // too poor for anything until the sandwiches are sold
+    game_state.step(&[Intent::BuyUpgrade(Upgrade::SharperTools)], 0.0);
+    assert_eq!(game_state.shop.get_level(&Upgrade::SharperTools), 0);
+
+    game_state.step(&[Intent::SellItem(Item::Sandwich, 10), Intent::BuyUpgrade(Upgrade::SharperTools)], 0.0);
+    assert_eq!(game_state.shop.get_level(&Upgrade::SharperTools), 1);
+    // every sandwich sold lowers the price of the next one: 146.7 coins for the ten
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 46);
+    assert_eq!(Upgrade::SharperTools.cost(1), 150);
+
+    game_state.step(&[Intent::BuyItem(Item::IronOre, 10), Intent::BuyItem(Item::IronBar, 1)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::IronOre), 0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::IronBar), 0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 46);
+}
+
+#[test]
+fn market_prices_recover_and_replay_under_a_seed() {
+    let mut market = Market::new(42);
+    let base = market.get_price(&Item::IronBar).unwrap();
+
+    assert_eq!(market.sell(&Item::IronBar, 100), 394);
+    let saturated = market.get_price(&Item::IronBar).unwrap();
+    assert!(saturated < base * 0.7);
+
+    market.update(3600.0);
+    let recovered = market.prices[&Item::IronBar].saturation;
+    assert!(recovered > 0.99);
+
+    let mut replay = Market::new(42);
+    replay.sell(&Item::IronBar, 100);
+    replay.update(3600.0);
+
+    for item in Item::all().iter().filter(|item| market.prices.contains_key(item)) {
+        assert_eq!(market.prices[item], replay.prices[item]);
+    }
+}
+
+#[test]
+fn achievements_unlock_from_production_and_persist() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+
+    game_state.step(&[Intent::SkipSeconds(1800)], 0.0);
+    assert!(!game_state.achievements.is_unlocked(&Achievement::Lumberjack));
+
+    game_state.step(&[Intent::SkipSeconds(3600)], 0.0);
+    assert!(game_state.achievements.is_unlocked(&Achievement::Lumberjack));
+    assert!(!game_state.achievements.is_unlocked(&Achievement::FullHouse));
+    assert!(game_state.achievements.get_produced(&Item::Wood(WoodItem::Craftwood)) >= 1_000);
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert!(loaded.achievements.is_unlocked(&Achievement::Lumberjack));
+    assert!(loaded.toasts.is_empty());
+}
+
+#[test]
+fn orders_are_posted_on_a_timer_and_pay_out() {
+    let mut game_state = GameState::new();
+
+    game_state.step(&[], 0.1);
+    assert_eq!(game_state.order_board.orders.len(), 1);
+
+    game_state.step(&[Intent::SkipSeconds(60 * 60)], 0.0);
+    assert_eq!(game_state.order_board.orders.len(), MAX_ORDERS);
+
+    let order = game_state.order_board.orders[0].clone();
+    assert!(!order.can_fulfill(&game_state));
+
+    for (item, amount) in &order.requests {
+        game_state.inventory.add_item(*item, *amount);
+    }
+
+    game_state.step(&[Intent::FulfillOrder(0)], 0.0);
+    assert_eq!(game_state.order_board.orders.len(), MAX_ORDERS - 1);
+
+    for (item, _) in &order.requests {
+        assert_eq!(game_state.inventory.get_item_amount(item), 0);
+    }
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.order_board.orders, game_state.order_board.orders);
+    assert_eq!(loaded.rng, game_state.rng);
+
+    // villagers only ask for what the player can make yet
+    let available_jobs: Vec<JobArchetype> = JobArchetype::all().into_iter().filter(|job| game_state.is_job_available(job)).collect();
+    for _ in 0..100 {
+        let order = generate_order(&mut game_state.rng, &game_state.skill_archetype_instances, &available_jobs);
+        for (item, _) in &order.requests {
+            assert!(available_jobs.iter().any(|job| job.get_product() == *item), "{} can't be made yet", item.get_name());
+        }
+    }
+}
+
+#[test]
+fn prestige_resets_the_run_but_keeps_renown_apart() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+
+    game_state.step(&[Intent::SkipSeconds(1800)], 0.0);
+    let renown = renown_for_reset(&game_state);
+    assert!(renown > 1);
+
+    game_state.step(&[Intent::Prestige, Intent::BuyPrestigePerk(PrestigePerk::Swiftness)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Wood(WoodItem::Craftwood)), 0);
+    assert!(matches!(game_state.job_slots[0].state, JobSlotState::Empty));
+    assert_eq!(game_state.prestige.renown, renown - 1);
+    assert_eq!(game_state.prestige.get_level(&PrestigePerk::Swiftness), 1);
+
+    // the run save doesn't know about renown, the prestige save does
+    let run = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(run.prestige, Prestige::new());
+    assert_eq!(Prestige::from_save_string(&game_state.prestige.to_save_string()), game_state.prestige);
+
+    // renown comes from lifetime progress, so the same run a second time is worth less
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.step(&[Intent::SkipSeconds(1800)], 0.0);
+    assert!(game_state.prestige.lifetime_actions > 0);
+    assert!(renown_for_reset(&game_state) < renown);
+}
+
+#[test]
+fn research_gates_skills_and_finishes_over_time() {
+    let mut game_state = GameState::new();
+    let potion_job = JobArchetype::Alchemy(AlchemyJobArchetype::ManaPotion);
+    let running_potion = || JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: potion_job }));
+    game_state.job_slots[0].set_state(JobSlotState::Empty);
+
+    // alchemy needs herbalism first
+    game_state.step(&[Intent::ChangeJobSlotState(0, running_potion())], 0.0);
+    assert!(matches!(game_state.job_slots[0].state, JobSlotState::Empty));
+
+    game_state.step(&[Intent::StartResearch(ResearchNode::Herbalism)], 0.0);
+    assert!(game_state.research.active.is_none());
+
+    game_state.inventory.add_item(Item::Herb, 30);
+    game_state.step(&[Intent::StartResearch(ResearchNode::Herbalism), Intent::StartResearch(ResearchNode::Carpentry)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Herb), 0);
+    assert_eq!(game_state.research.active.as_ref().map(|active| active.node), Some(ResearchNode::Herbalism));
+
+    game_state.step(&[Intent::SkipSeconds(60)], 0.0);
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.research.active, game_state.research.active);
+
+    game_state.step(&[Intent::SkipSeconds(30)], 0.0);
+    assert!(game_state.research.is_completed(&ResearchNode::Herbalism));
+    assert!(game_state.research.is_job_available(&potion_job));
+
+    game_state.step(&[Intent::ChangeJobSlotState(0, running_potion())], 0.0);
+    assert!(matches!(game_state.job_slots[0].state, JobSlotState::RunningJob(_)));
+
+    // the fifth slot can't be unlocked without expansion
+    assert_eq!(game_state.research.slot_limit(), 4);
+    game_state.inventory.add_item(Item::Woodworking(WoodWorkingItem::Plank), 1000);
+    game_state.step(&[Intent::UnlockJobSlot(4)], 0.0);
+    assert!(matches!(game_state.job_slots[4].state, JobSlotState::Locked));
+}
+
+#[test]
+fn village_buildings_cost_materials_and_raise_storage() {
+    let mut game_state = GameState::new();
+    let craftwood = Item::Wood(WoodItem::Craftwood);
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+
+    // a full storage stalls the job
+    game_state.inventory.add_item(craftwood, BASE_STORAGE);
+    game_state.step(&[Intent::SkipSeconds(60)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&craftwood), BASE_STORAGE);
+
+    game_state.step(&[Intent::ConstructBuilding(Building::Warehouse)], 0.0);
+    assert_eq!(game_state.village.get_level(&Building::Warehouse), 0);
+
+    game_state.inventory.add_item(Item::Woodworking(WoodWorkingItem::Plank), 40);
+    game_state.inventory.add_item(Item::IronBar, 20);
+    game_state.inventory.add_item(Item::Coin, 150);
+    game_state.step(&[Intent::ConstructBuilding(Building::Warehouse)], 0.0);
+    assert_eq!(game_state.village.get_level(&Building::Warehouse), 1);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 0);
+    assert_eq!(game_state.village.storage_capacity(), BASE_STORAGE + STORAGE_PER_WAREHOUSE_LEVEL);
+
+    game_state.step(&[Intent::SkipSeconds(60)], 0.0);
+    assert!(game_state.inventory.get_item_amount(&craftwood) > BASE_STORAGE);
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.village.get_level(&Building::Warehouse), 1);
+}
+
+#[test]
+fn socketed_runes_speed_up_the_slot_and_go_back_when_removed() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.inventory.add_item(Item::Rune(Rune::Time), 1);
+    game_state.inventory.add_item(Item::Rune(Rune::Blank), 1);
+
+    // blank runes can't be socketed, sockets are limited
+    game_state.step(&[Intent::SetSocket(0, 0, Some(Rune::Blank)), Intent::SetSocket(0, 2, Some(Rune::Time))], 0.0);
+    assert_eq!(game_state.job_slots[0].sockets, vec![None, None]);
+
+    game_state.step(&[Intent::SetSocket(0, 0, Some(Rune::GreaterTime)), Intent::SetSocket(0, 1, Some(Rune::Time))], 0.0);
+    assert_eq!(game_state.job_slots[0].sockets, vec![None, Some(Rune::Time)]);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Rune(Rune::Time)), 0);
+
+    let modifiers = game_state.slot_modifiers(&game_state.job_slots[0]);
+    assert!((modifiers.speed_multiplier - 1.15).abs() < 1e-9);
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.job_slots[0].sockets, game_state.job_slots[0].sockets);
+
+    game_state.step(&[Intent::SetSocket(0, 1, None)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Rune(Rune::Time)), 1);
+    assert_eq!(game_state.slot_modifiers(&game_state.job_slots[0]).speed_multiplier, 1.0);
+}
+
+#[test]
+fn hired_workers_level_up_on_their_slot_and_quit_unpaid() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+    game_state.workers.candidates.push(Worker::new("Ada", vec![WorkerTrait::FastGatherer], Upkeep::Sandwiches(2)));
+    let cost = game_state.workers.candidates[0].hire_cost();
+
+    game_state.step(&[Intent::HireWorker(0)], 0.0);
+    assert!(game_state.workers.hired.is_empty());
+
+    game_state.inventory.add_item(Item::Coin, cost);
+    game_state.step(&[Intent::HireWorker(0), Intent::AssignWorker(0, Some(0))], 0.0);
+    assert_eq!(game_state.workers.hired.len(), 1);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 0);
+
+    let modifiers = game_state.slot_modifiers(&game_state.job_slots[0]);
+    assert!((modifiers.speed_multiplier - 1.2).abs() < 1e-9);
+
+    game_state.inventory.add_item(Item::Sandwich, 2);
+    game_state.step(&[Intent::SkipSeconds(3660)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Sandwich), 0);
+    assert!(game_state.workers.hired[0].experience.level > 1);
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.workers.hired[0].name, "Ada");
+    assert_eq!(loaded.workers.hired[0].assigned_slot, Some(0));
+    assert_eq!(loaded.workers.hired[0].experience.level, game_state.workers.hired[0].experience.level);
+
+    // nobody pays the next sandwiches, so the worker leaves with their experience
+    game_state.step(&[Intent::SkipSeconds(3600)], 0.0);
+    assert!(game_state.workers.hired.is_empty());
+}
+
+#[test]
+fn heavy_jobs_take_more_time_slots_and_capacity_can_be_bought() {
+    let mut game_state = GameState::new();
+    game_state.research.completed.push(ResearchNode::Smelting);
+    for slot in game_state.job_slots.iter_mut() {
+        slot.state = JobSlotState::Empty;
+    }
+
+    let smelt = |index| Intent::ChangeJobSlotState(index, JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Smithing(SmithingJobArchetype::IronBar),
+    })));
+
+    game_state.step(&[smelt(0), smelt(1), smelt(2)], 0.0);
+    assert_eq!(game_state.timeslots_used(None), 4);
+    assert!(matches!(game_state.job_slots[2].state, JobSlotState::Empty));
+
+    // a cheap job still fits into the last time slot, replacing a running job frees its own
+    game_state.step(&[Intent::ChangeJobSlotState(2, JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    })))], 0.0);
+    game_state.step(&[smelt(0)], 0.0);
+    assert_eq!(game_state.timeslots_used(None), 5);
+
+    // pausing frees the time slots, resuming needs them back
+    game_state.step(&[Intent::ToggleJob(0), smelt(3), Intent::ToggleJob(0)], 0.0);
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[0].state else { panic!("slot should be running") };
+    assert!(!job_instance.running);
+
+    game_state.inventory.add_item(Item::Coin, 300);
+    game_state.step(&[Intent::BuyUpgrade(Upgrade::LongerShifts), Intent::ToggleJob(0)], 0.0);
+    assert_eq!(game_state.timeslot_capacity(), 6);
+    assert!(matches!(&game_state.job_slots[3].state, JobSlotState::RunningJob(_)));
+    assert!(matches!(&game_state.job_slots[0].state, JobSlotState::RunningJob(job_instance) if !job_instance.running));
+}
+
+#[test]
+fn clicking_a_job_speeds_it_up_with_diminishing_returns() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Lumbering(LumberingJobArchetype::Craftwood),
+    }));
+
+    // the first update pays for the action, clicks only help a job that is working
+    game_state.step(&[Intent::ClickJob(0)], 0.0);
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[0].state else { panic!("slot should be running") };
+    assert_eq!(job_instance.time_accumulator, 0.0);
+
+    // a burst of clicks in the same moment counts once
+    game_state.step(&[Intent::ClickJob(0), Intent::ClickJob(0), Intent::ClickJob(0)], 0.0);
+    let JobSlotState::RunningJob(job_instance) = &game_state.job_slots[0].state else { panic!("slot should be running") };
+    let first_click = job_instance.time_accumulator;
+    assert!(first_click >= CLICK_PROGRESS_SECONDS);
+    assert_eq!(job_instance.clicks.hyper_charge, 1);
+
+    let mut job_instance = job_instance.clone();
+    let mut rng = Rng::new(7);
+    let mut gains = vec![];
+
+    for _ in 0..CLICKS_PER_HYPER_ACTION {
+        job_instance.clicks.update(MIN_CLICK_INTERVAL as f32);
+        job_instance.time_accumulator = 0.0;
+        gains.push(job_instance.clicks.next_progress());
+        assert!(job_instance.click(&mut rng).is_some());
+    }
+
+    assert!(gains.windows(2).all(|pair| pair[1] < pair[0]));
+    assert_eq!(job_instance.hyper_mode.actions_counter, 1);
+}
+
+#[test]
+fn gifts_raise_affection_and_unlock_villager_perks() {
+    let mut game_state = GameState::new();
+
+    game_state.step(&[Intent::GiftItem(Villager::Wren, Item::ManaPotion)], 0.0);
+    assert_eq!(game_state.villagers.get_affection(&Villager::Wren), 0);
+
+    // coins are no gift, loved items count more than anything else
+    game_state.inventory.add_item(Item::Coin, 10);
+    game_state.inventory.add_item(Item::ManaPotion, 30);
+    game_state.inventory.add_item(Item::IronOre, 1);
+    game_state.step(&[Intent::GiftItem(Villager::Wren, Item::Coin), Intent::GiftItem(Villager::Wren, Item::IronOre)], 0.0);
+    assert_eq!(game_state.villagers.get_affection(&Villager::Wren), 1);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 10);
+
+    let gifts: Vec<Intent> = (0..20).map(|_| Intent::GiftItem(Villager::Wren, Item::ManaPotion)).collect();
+    game_state.step(&gifts, 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 10);
+    assert!(game_state.villagers.get_affection(&Villager::Wren) >= AFFECTION_LEVELS[2]);
+    assert_eq!(game_state.villagers.get_level(&Villager::Wren), 3);
+
+    // Wren teaches runecraft, and the foraging bonus applies to herb gathering
+    assert!(game_state.research.is_completed(&ResearchNode::Runecraft));
+    let modifiers = game_state.job_modifiers(&JobInstance::new(JobParameters {
+        job_archetype: JobArchetype::Foraging(ForagingJobArchetype::Herb),
+    }));
+    assert!((modifiers.speed_multiplier - 1.15).abs() < 1e-9);
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.villagers.get_level(&Villager::Wren), 3);
+}
+
+#[test]
+fn villager_discounts_never_undercut_a_spiked_market() {
+    let mut game_state = GameState::new();
+    game_state.villagers.affection.insert(Villager::Mara, AFFECTION_LEVELS[2]);
+    assert!((game_state.villagers.shop_discount() - 0.2).abs() < 1e-9);
+
+    // 20% off 3 coins rounds up, a 1 coin item never gets cheaper than it sells for in a spike
+    assert_eq!(discounted_buy_price(&game_state, &Item::Wood(WoodItem::Craftwood)), Some(3));
+    assert_eq!(discounted_buy_price(&game_state, &Item::Herb), Some(3));
+    assert_eq!(discounted_buy_price(&game_state, &Item::Meat), Some(5));
+
+    for item in SHOP_STOCK {
+        let highest_sell_price = base_price(&item).unwrap() as f64 * MAX_SPIKE_FACTOR;
+        assert!(discounted_buy_price(&game_state, &item).unwrap() as f64 >= highest_sell_price);
+    }
+}
+
+#[test]
+fn claimed_tiles_gate_graintree_and_add_mining_yield() {
+    let mut game_state = GameState::new();
+    game_state.job_slots[0].state = JobSlotState::Empty;
+    let graintree = JobArchetype::Lumbering(LumberingJobArchetype::Graintree);
+    let iron = JobInstance::new(JobParameters { job_archetype: JobArchetype::Mining(MiningJobArchetype::Iron) });
+
+    game_state.step(&[Intent::ChangeJobSlotState(0, JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: graintree })))], 0.0);
+    assert!(matches!(game_state.job_slots[0].state, JobSlotState::Empty));
+
+    // only tiles next to the village can be claimed at first
+    let village = *game_state.territory.tiles.iter().find(|tile| tile.kind == TileKind::Village).unwrap();
+    let mine = *game_state.territory.tiles.iter().find(|tile| tile.kind == TileKind::Mine && tile.row == village.row - 1 && tile.col == village.col).unwrap();
+    let far_mine = *game_state.territory.tiles.iter().find(|tile| tile.kind == TileKind::Mine && tile.row == 0).unwrap();
+
+    game_state.inventory.add_item(Item::Coin, 1000);
+    game_state.inventory.add_item(Item::Wood(WoodItem::Craftwood), 100);
+    game_state.inventory.add_item(Item::Woodworking(WoodWorkingItem::Plank), 100);
+    game_state.step(&[Intent::ClaimTile(far_mine.col, far_mine.row), Intent::ClaimTile(mine.col, mine.row)], 0.0);
+    assert!(!game_state.territory.is_claimed(far_mine.col, far_mine.row));
+    assert_eq!(game_state.territory.count(&TileKind::Mine), 1);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 920);
+    assert!((game_state.job_modifiers(&iron).yield_multiplier - (1.0 + TILE_YIELD_BONUS)).abs() < 1e-9);
+
+    // walk over to the grain forest on the left edge
+    let path = (0..village.col).rev().map(|col| Intent::ClaimTile(col, village.row)).collect::<Vec<_>>();
+    game_state.step(&path, 0.0);
+    assert_eq!(game_state.territory.count(&TileKind::GrainForest), 1);
+    assert!(game_state.is_job_available(&graintree));
+
+    let loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.territory.claimed_count(), game_state.territory.claimed_count());
+    assert!(loaded.is_job_available(&graintree));
+}
+
+#[test]
+fn expeditions_return_with_loot_after_skipping_or_offline_time() {
+    let mut game_state = GameState::new();
+    game_state.workers.hired.push(Worker::new("Bram", vec![WorkerTrait::FastGatherer], Upkeep::Coins(20)));
+    game_state.step(&[Intent::AssignWorker(0, Some(0)), Intent::StartExpedition(Destination::BerryHills, vec![0])], 0.0);
+    assert!(game_state.expeditions.active.is_empty());
+
+    // the party eats its supplies on the way and leaves the slot it worked
+    game_state.inventory.add_item(Item::Sandwich, 2);
+    game_state.step(&[Intent::StartExpedition(Destination::BerryHills, vec![0])], 0.0);
+    assert_eq!(game_state.expeditions.active.len(), 1);
+    assert!(game_state.workers.hired.is_empty());
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Sandwich), 0);
+
+    game_state.step(&[Intent::SkipSeconds(300)], 0.0);
+    assert_eq!(game_state.expeditions.active.len(), 1);
+
+    // the rest of the trip happens while the game is closed
+    let mut loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.expeditions.active[0].party[0].name, "Bram");
+    loaded.catch_up_offline(300.0);
+
+    assert!(loaded.expeditions.active.is_empty());
+    assert_eq!(loaded.workers.hired.len(), 1);
+    assert_eq!(loaded.workers.hired[0].assigned_slot, None);
+    assert_eq!(loaded.workers.hired[0].experience.actions_done_total, 20);
+    assert!(loaded.inventory.get_item_amount(&Item::Berry) >= 5);
+}
+
+#[test]
+fn workers_away_on_expeditions_count_towards_the_cap() {
+    let mut game_state = GameState::new();
+    game_state.inventory.add_item(Item::Coin, 100_000);
+    for i in 0..=MAX_WORKERS {
+        game_state.workers.candidates.push(Worker::new(&format!("Worker {}", i), vec![WorkerTrait::FastGatherer], Upkeep::Coins(20)));
+    }
+
+    let hires: Vec<Intent> = (0..MAX_WORKERS).map(|_| Intent::HireWorker(0)).collect();
+    game_state.step(&hires, 0.0);
+    assert_eq!(game_state.workers.hired.len(), MAX_WORKERS);
+
+    // the worker on the trip keeps their place on the roster
+    game_state.inventory.add_item(Item::Sandwich, 2);
+    game_state.step(&[Intent::StartExpedition(Destination::BerryHills, vec![0]), Intent::HireWorker(0)], 0.0);
+    assert_eq!(game_state.expeditions.active.len(), 1);
+    assert_eq!(game_state.workers.hired.len(), MAX_WORKERS - 1);
+    assert_eq!(worker_count(&game_state), MAX_WORKERS);
+
+    game_state.step(&[Intent::SkipSeconds(600)], 0.0);
+    assert!(game_state.expeditions.active.is_empty());
+    assert!(game_state.workers.hired.len() <= MAX_WORKERS);
+}
+
+#[test]
+fn offline_catch_up_stays_quick_with_every_slot_busy() {
+    let mut game_state = GameState::new();
+    for (slot, job_archetype) in game_state.job_slots.iter_mut().zip(JobArchetype::all()) {
+        slot.state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype }));
+    }
+
+    // the game catches up before the first frame, so the longest absence must not hold up the start
+    let started = std::time::Instant::now();
+    game_state.catch_up_offline(MAX_OFFLINE_SECONDS);
+    assert!(started.elapsed().as_secs_f64() < 5.0, "catching up took {:?}", started.elapsed());
+    assert!(game_state.job_archetype_instances.instances.iter().any(|job| job.action_counter.actions_done_total > 0));
+}
+
+#[test]
+fn fast_jobs_make_as_much_in_one_catch_up_as_in_short_ticks() {
+    let craftwood = JobArchetype::Lumbering(LumberingJobArchetype::Craftwood);
+    let mut short_ticks = GameState::new();
+    let mut catch_up = GameState::new();
+
+    // hyper mode and an axe finish more than one action per second
+    for game_state in [&mut short_ticks, &mut catch_up] {
+        let mut job_instance = JobInstance::new(JobParameters { job_archetype: craftwood });
+        job_instance.hyper_mode.is_enabled = true;
+        job_instance.hyper_mode.hyper_duration_seconds = 1000.0;
+        game_state.job_slots[0].state = JobSlotState::RunningJob(job_instance);
+        game_state.inventory.add_item(Item::Tool(Tool::Axe), 1);
+        game_state.step(&[Intent::EquipTool(Tool::Axe)], 0.0);
+    }
+
+    for _ in 0..600 {
+        short_ticks.step(&[], 0.1);
+    }
+    catch_up.catch_up_offline(60.0);
+
+    let craftwood_made = |game_state: &GameState| game_state.inventory.get_item_amount(&Item::Wood(WoodItem::Craftwood));
+    assert_eq!(craftwood_made(&short_ticks), 75);
+    assert_eq!(craftwood_made(&catch_up), 75);
+}
+
+#[test]
+fn combat_kills_enemies_using_potions_and_food_until_beaten() {
+    let mut game_state = GameState::new();
+    game_state.step(&[Intent::StartFight(Enemy::Bandit)], 0.0);
+    assert!(game_state.combat.fight.is_none());
+
+    game_state.inventory.add_item(Item::ManaPotion, 2);
+    game_state.step(&[Intent::StartFight(Enemy::Wolf), Intent::SkipSeconds(60)], 0.0);
+
+    assert!(game_state.combat.kills > 0);
+    assert!(game_state.combat.experience.actions_done_total >= 3 * game_state.combat.kills);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::ManaPotion), 0);
+    assert!(game_state.inventory.get_item_amount(&Item::Meat) > 0);
+
+    let mut loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.combat.kills, game_state.combat.kills);
+    assert_eq!(loaded.combat.fight.as_ref().map(|fight| fight.enemy), Some(Enemy::Wolf));
+
+    // hurt and without food, the next bites end the fight
+    loaded.combat.hp = 1.0;
+    loaded.inventory.item_amounts.remove(&Item::Meat);
+    loaded.step(&[Intent::SkipSeconds(10)], 0.0);
+    assert!(loaded.combat.fight.is_none());
+
+    loaded.step(&[Intent::StartFight(Enemy::Wolf)], 0.0);
+    assert!(loaded.combat.fight.is_none());
+
+    // resting brings the health back
+    loaded.step(&[Intent::SkipSeconds(60), Intent::StartFight(Enemy::Wolf)], 0.0);
+    assert!(loaded.combat.fight.is_some());
+
+    // the firebolt cooldown runs hit by hit, so one long step casts as often as many short ones
+    let mut short_steps = GameState::new();
+    let mut long_step = GameState::new();
+    for game_state in [&mut short_steps, &mut long_step] {
+        game_state.inventory.add_item(Item::ManaPotion, 5);
+        game_state.step(&[Intent::StartFight(Enemy::Wolf)], 0.0);
+    }
+
+    for _ in 0..40 {
+        short_steps.step(&[], 1.0);
+    }
+    long_step.step(&[], 40.0);
+    assert_eq!(long_step.inventory.get_item_amount(&Item::ManaPotion), 2);
+    assert_eq!(short_steps.inventory.get_item_amount(&Item::ManaPotion), 2);
+}
+
+#[test]
+fn veins_run_dry_until_prospected_and_groves_grow_back() {
+    let mut game_state = GameState::new();
+    let iron = JobArchetype::Mining(MiningJobArchetype::Iron);
+    game_state.job_slots[0].state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: iron }));
+
+    game_state.step(&[Intent::SkipSeconds(1500)], 0.0);
+    let actions = game_state.job_archetype_instances.get_archetype(&iron).action_counter.actions_done_total;
+    assert_eq!(actions, 300);
+    assert!(game_state.resource_nodes.get_node(&iron).unwrap().is_exhausted());
+
+    game_state.step(&[Intent::Prospect(iron)], 0.0);
+    assert!(game_state.resource_nodes.prospecting.is_none());
+
+    game_state.inventory.add_item(Item::Coin, PROSPECT_COST);
+    game_state.step(&[Intent::Prospect(iron), Intent::SkipSeconds(30)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Coin), 0);
+
+    let mut loaded = GameState::from_save_string(&game_state.to_save_string());
+    assert_eq!(loaded.resource_nodes.prospecting, game_state.resource_nodes.prospecting);
+
+    loaded.step(&[Intent::SkipSeconds(60)], 0.0);
+    let vein = loaded.resource_nodes.get_node(&iron).unwrap();
+    assert!(vein.capacity >= PROSPECTED_VEIN_CAPACITY.0 && vein.capacity <= PROSPECTED_VEIN_CAPACITY.1);
+    assert!(vein.remaining < vein.capacity);
+    assert!(loaded.job_archetype_instances.get_archetype(&iron).action_counter.actions_done_total > actions);
+
+    // two slots cut a grove faster than it grows, then wait for it
+    let craftwood = JobArchetype::Lumbering(LumberingJobArchetype::Craftwood);
+    for index in 0..2 {
+        loaded.job_slots[index].state = JobSlotState::RunningJob(JobInstance::new(JobParameters { job_archetype: craftwood }));
+    }
+
+    loaded.step(&[Intent::SkipSeconds(600)], 0.0);
+    let grove_actions = loaded.job_archetype_instances.get_archetype(&craftwood).action_counter.actions_done_total;
+    assert!(grove_actions < 300);
+    assert!(grove_actions >= 240);
+}
+
+#[test]
+fn food_spoils_oldest_first_unless_kept_in_a_cellar() {
+    let mut game_state = GameState::new();
+    game_state.inventory.add_item(Item::Meat, 10);
+    game_state.step(&[Intent::SkipSeconds(25 * 60)], 0.0);
+    assert_eq!(game_state.inventory.get_item_amount(&Item::Meat), 10);
+    assert_eq!(game_state.spoilage.spoiling_soon(&Item::Meat), 10);
+
+    // eating takes the oldest meat, fresh meat starts over
+    game_state.inventory.add_item(Item::Meat, -4);
+    game_state.step(&[], 0.0);
+    game_state.inventory.add_item(Item::Meat, 5);
+    game_state.step(&[], 0.0);
+    assert_eq!(game_state.spoilage.spoiling_soon(&Item::Meat), 6);
+
+    let mut loaded = GameState::from_save_string(&game_state.to_save_string());
+    loaded.step(&[Intent::SkipSeconds(5 * 60)], 0.0);
+    assert_eq!(loaded.inventory.get_item_amount(&Item::Meat), 5);
+    assert_eq!(loaded.spoilage.spoiling_soon(&Item::Meat), 0);
+
+    // sandwiches keep for hours, longer still with a cellar
+    loaded.village.building_levels.insert(Building::Cellar, 1);
+    loaded.inventory.add_item(Item::Sandwich, 10);
+    loaded.catch_up_offline(5.0 * 60.0 * 60.0);
+    assert_eq!(loaded.inventory.get_item_amount(&Item::Sandwich), 10);
+    assert_eq!(loaded.inventory.get_item_amount(&Item::Meat), 0);
+
+    loaded.step(&[Intent::SkipSeconds(2 * 60 * 60)], 0.0);
+    assert_eq!(loaded.inventory.get_item_amount(&Item::Sandwich), 0);
+}